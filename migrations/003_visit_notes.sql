@@ -0,0 +1,2 @@
+-- Optional free-form note attached to each visit ("what did I learn this time?")
+ALTER TABLE visits ADD COLUMN note TEXT;
@@ -0,0 +1,10 @@
+-- One row per edit of an entry, holding the field-level diff as JSON
+CREATE TABLE entry_revisions (
+    id TEXT PRIMARY KEY,
+    entry_id TEXT NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+    user_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    changes TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_entry_revisions_entry_id ON entry_revisions(entry_id);
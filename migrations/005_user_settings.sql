@@ -0,0 +1,9 @@
+-- Per-user defaults for new entries (form, quick-add and import)
+CREATE TABLE user_settings (
+    user_id TEXT PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    default_duration INTEGER NOT NULL DEFAULT 1,
+    default_interval TEXT NOT NULL DEFAULT 'days' CHECK (default_interval IN ('hours', 'days', 'weeks', 'months', 'years')),
+    default_collection_id TEXT REFERENCES collections(id) ON DELETE SET NULL,
+    default_tags TEXT NOT NULL DEFAULT '',
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,3 @@
+-- Priority ranks due entries; pinned entries stay at the top of every list
+ALTER TABLE entries ADD COLUMN priority TEXT NOT NULL DEFAULT 'normal' CHECK (priority IN ('low', 'normal', 'high'));
+ALTER TABLE entries ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
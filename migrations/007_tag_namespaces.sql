@@ -0,0 +1,62 @@
+-- Scope tags to a namespace: a user's private entries, or a collection's
+-- shared entries. The old global rows are split into one row per namespace
+-- that actually uses them; tags no entry uses are dropped.
+
+CREATE TEMP TABLE tag_split AS
+SELECT DISTINCT
+    et.tag_id AS old_id,
+    t.name,
+    t.created_at,
+    CASE WHEN e.collection_id IS NULL THEN e.user_id END AS user_id,
+    e.collection_id
+FROM entry_tags et
+JOIN entries e ON e.id = et.entry_id
+JOIN tags t ON t.id = et.tag_id;
+
+ALTER TABLE tag_split ADD COLUMN new_id TEXT;
+
+-- Random v4-style UUID per row
+UPDATE tag_split SET new_id =
+    lower(hex(randomblob(4))) || '-' ||
+    lower(hex(randomblob(2))) || '-4' ||
+    substr(lower(hex(randomblob(2))), 2) || '-' ||
+    substr('89ab', 1 + (abs(random()) % 4), 1) ||
+    substr(lower(hex(randomblob(2))), 2) || '-' ||
+    lower(hex(randomblob(6)));
+
+CREATE TABLE tags_new (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    user_id TEXT REFERENCES users(id) ON DELETE CASCADE,
+    collection_id TEXT REFERENCES collections(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    CHECK ((user_id IS NULL) != (collection_id IS NULL))
+);
+
+CREATE TABLE entry_tags_new (
+    entry_id TEXT NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES tags_new(id) ON DELETE CASCADE,
+    PRIMARY KEY (entry_id, tag_id)
+);
+
+INSERT INTO tags_new (id, name, user_id, collection_id, created_at)
+SELECT new_id, name, user_id, collection_id, created_at FROM tag_split;
+
+INSERT INTO entry_tags_new (entry_id, tag_id)
+SELECT et.entry_id, s.new_id
+FROM entry_tags et
+JOIN entries e ON e.id = et.entry_id
+JOIN tag_split s ON s.old_id = et.tag_id
+    AND s.collection_id IS e.collection_id
+    AND s.user_id IS (CASE WHEN e.collection_id IS NULL THEN e.user_id END);
+
+DROP TABLE entry_tags;
+DROP TABLE tags;
+DROP TABLE tag_split;
+
+ALTER TABLE tags_new RENAME TO tags;
+ALTER TABLE entry_tags_new RENAME TO entry_tags;
+
+CREATE UNIQUE INDEX idx_tags_user_name ON tags(user_id, name) WHERE user_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_tags_collection_name ON tags(collection_id, name) WHERE collection_id IS NOT NULL;
+CREATE INDEX idx_entry_tags_tag_id ON entry_tags(tag_id);
@@ -0,0 +1,4 @@
+-- A tag can carry a default schedule for new entries added with it. Both
+-- columns are set together, or neither.
+ALTER TABLE tags ADD COLUMN default_duration INTEGER;
+ALTER TABLE tags ADD COLUMN default_interval TEXT;
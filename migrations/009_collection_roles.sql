@@ -0,0 +1,3 @@
+-- What a member may do in a collection. Existing members could already add
+-- entries, so they become editors. The owner is not a member row.
+ALTER TABLE collection_members ADD COLUMN role TEXT NOT NULL DEFAULT 'editor' CHECK (role IN ('viewer', 'editor', 'admin'));
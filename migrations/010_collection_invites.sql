@@ -0,0 +1,28 @@
+-- Several invite links per collection, each with its own limits and the role
+-- new members get. Each collection's existing code becomes its first invite
+-- so links already shared keep working. `collections.invite_code` stays (a
+-- UNIQUE column can't be dropped) but is no longer used to join.
+CREATE TABLE collection_invites (
+    id TEXT PRIMARY KEY,
+    collection_id TEXT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    code TEXT NOT NULL UNIQUE,
+    label TEXT,
+    role TEXT NOT NULL DEFAULT 'editor' CHECK (role IN ('viewer', 'editor', 'admin')),
+    max_uses INTEGER,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    expires_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_collection_invites_collection_id ON collection_invites(collection_id);
+
+INSERT INTO collection_invites (id, collection_id, code, created_at)
+SELECT
+    lower(hex(randomblob(4))) || '-' ||
+    lower(hex(randomblob(2))) || '-4' ||
+    substr(lower(hex(randomblob(2))), 2) || '-' ||
+    substr('89ab', 1 + (abs(random()) % 4), 1) ||
+    substr(lower(hex(randomblob(2))), 2) || '-' ||
+    lower(hex(randomblob(6))),
+    id, invite_code, created_at
+FROM collections;
@@ -0,0 +1,28 @@
+-- When a user is deleted, hand each collection they own to its most senior
+-- member (admins first, then editors, then viewers; earliest joined first)
+-- instead of letting the collection cascade away. Collections with no members
+-- are still deleted, but links other people added to them become private
+-- rather than disappearing with it.
+CREATE TRIGGER reassign_collections_before_user_delete
+BEFORE DELETE ON users
+FOR EACH ROW
+BEGIN
+    UPDATE collections SET
+        owner_id = (
+            SELECT cm.user_id FROM collection_members cm
+            WHERE cm.collection_id = collections.id
+            ORDER BY CASE cm.role WHEN 'admin' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END, cm.joined_at
+            LIMIT 1
+        ),
+        updated_at = strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now')
+    WHERE owner_id = OLD.id
+    AND EXISTS (SELECT 1 FROM collection_members cm WHERE cm.collection_id = collections.id);
+
+    -- The new owners aren't members any more
+    DELETE FROM collection_members
+    WHERE user_id IN (SELECT owner_id FROM collections WHERE id = collection_members.collection_id);
+
+    UPDATE entries SET collection_id = NULL
+    WHERE user_id != OLD.id
+    AND collection_id IN (SELECT id FROM collections WHERE owner_id = OLD.id);
+END;
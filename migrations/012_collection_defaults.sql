@@ -0,0 +1,5 @@
+-- Defaults for new entries added to a collection. The schedule is used like a
+-- tag's: only when the entry doesn't set one of its own.
+ALTER TABLE collections ADD COLUMN default_duration INTEGER;
+ALTER TABLE collections ADD COLUMN default_interval TEXT CHECK (default_interval IN ('hours', 'days', 'weeks', 'months', 'years'));
+ALTER TABLE collections ADD COLUMN default_tags TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,14 @@
+-- What happens in a collection, for its activity feed. `subject` keeps the
+-- entry title or member name as it was, so events still read sensibly after
+-- the entry or user is gone.
+CREATE TABLE collection_events (
+    id TEXT PRIMARY KEY,
+    collection_id TEXT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    user_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('entry_added', 'entry_edited', 'entry_removed', 'visited', 'member_joined', 'member_left', 'member_removed')),
+    entry_id TEXT REFERENCES entries(id) ON DELETE SET NULL,
+    subject TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_collection_events_collection_id ON collection_events(collection_id);
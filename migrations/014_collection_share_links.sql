@@ -0,0 +1,5 @@
+-- Optional public read-only link to a collection. NULL when not shared;
+-- replacing or clearing the token revokes the old link.
+ALTER TABLE collections ADD COLUMN share_token TEXT;
+
+CREATE UNIQUE INDEX idx_collections_share_token ON collections(share_token) WHERE share_token IS NOT NULL;
@@ -0,0 +1,14 @@
+-- Threaded discussion on collection entries. A deleted comment that has
+-- replies keeps its row (with `deleted_at` set) so the thread stays intact.
+CREATE TABLE entry_comments (
+    id TEXT PRIMARY KEY,
+    entry_id TEXT NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+    user_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    parent_id TEXT REFERENCES entry_comments(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    deleted_at TEXT
+);
+
+CREATE INDEX idx_entry_comments_entry_id ON entry_comments(entry_id);
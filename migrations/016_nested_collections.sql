@@ -0,0 +1,24 @@
+-- Collections can sit under a parent collection. Deleting a parent moves its
+-- children to the top level.
+ALTER TABLE collections ADD COLUMN parent_id TEXT REFERENCES collections(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_collections_parent_id ON collections(parent_id);
+
+-- Everyone who can see each collection, with their best role. Access is
+-- inherited downward: members of a collection are members of every
+-- collection below it with the same role, and its owner is an admin there.
+CREATE VIEW collection_access AS
+WITH RECURSIVE grants(collection_id, user_id, role, rank) AS (
+    SELECT id, owner_id, 'owner', 4 FROM collections
+    UNION
+    SELECT collection_id, user_id, role, CASE role WHEN 'admin' THEN 3 WHEN 'editor' THEN 2 ELSE 1 END
+    FROM collection_members
+    UNION
+    SELECT c.id, g.user_id, CASE g.role WHEN 'owner' THEN 'admin' ELSE g.role END, MIN(g.rank, 3)
+    FROM grants g
+    JOIN collections c ON c.parent_id = g.collection_id
+)
+-- SQLite takes the bare `role` from the row with the highest rank
+SELECT collection_id, user_id, role, MAX(rank) AS rank
+FROM grants
+GROUP BY collection_id, user_id;
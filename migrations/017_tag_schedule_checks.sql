@@ -0,0 +1,42 @@
+-- Constrain tag schedules like collection defaults: a known interval, and
+-- both columns set together or neither. SQLite can't add a CHECK to an
+-- existing table, so tags (and entry_tags, which references it) are rebuilt.
+-- A half-set schedule is cleared.
+
+CREATE TABLE tags_new (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    user_id TEXT REFERENCES users(id) ON DELETE CASCADE,
+    collection_id TEXT REFERENCES collections(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    default_duration INTEGER,
+    default_interval TEXT CHECK (default_interval IN ('hours', 'days', 'weeks', 'months', 'years')),
+    CHECK ((user_id IS NULL) != (collection_id IS NULL)),
+    CHECK ((default_duration IS NULL) = (default_interval IS NULL))
+);
+
+CREATE TABLE entry_tags_new (
+    entry_id TEXT NOT NULL REFERENCES entries(id) ON DELETE CASCADE,
+    tag_id TEXT NOT NULL REFERENCES tags_new(id) ON DELETE CASCADE,
+    PRIMARY KEY (entry_id, tag_id)
+);
+
+INSERT INTO tags_new (id, name, user_id, collection_id, created_at, default_duration, default_interval)
+SELECT id, name, user_id, collection_id, created_at,
+    CASE WHEN default_interval IN ('hours', 'days', 'weeks', 'months', 'years') THEN default_duration END,
+    CASE WHEN default_duration IS NOT NULL AND default_interval IN ('hours', 'days', 'weeks', 'months', 'years')
+        THEN default_interval END
+FROM tags;
+
+INSERT INTO entry_tags_new (entry_id, tag_id)
+SELECT entry_id, tag_id FROM entry_tags;
+
+DROP TABLE entry_tags;
+DROP TABLE tags;
+
+ALTER TABLE tags_new RENAME TO tags;
+ALTER TABLE entry_tags_new RENAME TO entry_tags;
+
+CREATE UNIQUE INDEX idx_tags_user_name ON tags(user_id, name) WHERE user_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_tags_collection_name ON tags(collection_id, name) WHERE collection_id IS NOT NULL;
+CREATE INDEX idx_entry_tags_tag_id ON entry_tags(tag_id);
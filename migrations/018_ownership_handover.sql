@@ -0,0 +1,93 @@
+-- Keep a deleted owner's links in the collections handed to another member.
+-- Entries cascade away with their author, so the ones in those collections
+-- pass to the new owner first. Links other people added to a collection that
+-- is deleted with its owner keep their tags: the collection's tags go with
+-- the collection, so each author gets their own copy first. Otherwise the
+-- trigger is as in 011.
+DROP TRIGGER reassign_collections_before_user_delete;
+
+CREATE TRIGGER reassign_collections_before_user_delete
+BEFORE DELETE ON users
+FOR EACH ROW
+BEGIN
+    -- Their links in collections about to be handed over go with the collection
+    UPDATE entries SET user_id = (
+        SELECT cm.user_id FROM collection_members cm
+        WHERE cm.collection_id = entries.collection_id
+        ORDER BY CASE cm.role WHEN 'admin' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END, cm.joined_at
+        LIMIT 1
+    )
+    WHERE user_id = OLD.id
+    AND collection_id IN (
+        SELECT c.id FROM collections c
+        WHERE c.owner_id = OLD.id
+        AND EXISTS (SELECT 1 FROM collection_members cm WHERE cm.collection_id = c.id)
+    );
+
+    UPDATE collections SET
+        owner_id = (
+            SELECT cm.user_id FROM collection_members cm
+            WHERE cm.collection_id = collections.id
+            ORDER BY CASE cm.role WHEN 'admin' THEN 0 WHEN 'editor' THEN 1 ELSE 2 END, cm.joined_at
+            LIMIT 1
+        ),
+        updated_at = strftime('%Y-%m-%dT%H:%M:%S+00:00', 'now')
+    WHERE owner_id = OLD.id
+    AND EXISTS (SELECT 1 FROM collection_members cm WHERE cm.collection_id = collections.id);
+
+    -- The new owners aren't members any more
+    DELETE FROM collection_members
+    WHERE user_id IN (SELECT owner_id FROM collections WHERE id = collection_members.collection_id);
+
+    -- Collections still owned by OLD have nobody to take them over
+    INSERT OR IGNORE INTO tags (id, name, user_id, default_duration, default_interval)
+    SELECT
+        lower(hex(randomblob(4))) || '-' ||
+        lower(hex(randomblob(2))) || '-4' ||
+        substr(lower(hex(randomblob(2))), 2) || '-' ||
+        substr('89ab', 1 + (abs(random()) % 4), 1) ||
+        substr(lower(hex(randomblob(2))), 2) || '-' ||
+        lower(hex(randomblob(6))),
+        t.name, e.user_id, t.default_duration, t.default_interval
+    FROM entries e
+    JOIN entry_tags et ON et.entry_id = e.id
+    JOIN tags t ON t.id = et.tag_id
+    WHERE e.user_id != OLD.id
+    AND e.collection_id IN (SELECT id FROM collections WHERE owner_id = OLD.id);
+
+    INSERT OR IGNORE INTO entry_tags (entry_id, tag_id)
+    SELECT e.id, own.id
+    FROM entries e
+    JOIN entry_tags et ON et.entry_id = e.id
+    JOIN tags t ON t.id = et.tag_id
+    JOIN tags own ON own.user_id = e.user_id AND own.name = t.name
+    WHERE e.user_id != OLD.id
+    AND e.collection_id IN (SELECT id FROM collections WHERE owner_id = OLD.id);
+
+    UPDATE entries SET collection_id = NULL
+    WHERE user_id != OLD.id
+    AND collection_id IN (SELECT id FROM collections WHERE owner_id = OLD.id);
+END;
+
+-- Ownership transfers show up in the activity feed. SQLite can't change a
+-- CHECK, so the table is rebuilt.
+CREATE TABLE collection_events_new (
+    id TEXT PRIMARY KEY,
+    collection_id TEXT NOT NULL REFERENCES collections(id) ON DELETE CASCADE,
+    user_id TEXT REFERENCES users(id) ON DELETE SET NULL,
+    kind TEXT NOT NULL CHECK (kind IN (
+        'entry_added', 'entry_edited', 'entry_removed', 'visited',
+        'member_joined', 'member_left', 'member_removed', 'ownership_transferred'
+    )),
+    entry_id TEXT REFERENCES entries(id) ON DELETE SET NULL,
+    subject TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO collection_events_new (id, collection_id, user_id, kind, entry_id, subject, created_at)
+SELECT id, collection_id, user_id, kind, entry_id, subject, created_at FROM collection_events;
+
+DROP TABLE collection_events;
+ALTER TABLE collection_events_new RENAME TO collection_events;
+
+CREATE INDEX idx_collection_events_collection_id ON collection_events(collection_id);
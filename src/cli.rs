@@ -3,7 +3,9 @@ use sqlx::SqlitePool;
 use std::fs;
 use uuid::Uuid;
 
-use crate::models::Interval;
+use crate::models::{Interval, Priority, Tag, TagNamespace, UserSettings};
+use crate::routes::export::{import_collection_bundle, parse_collection_bundle};
+use crate::routes::entries::{fetch_tag_schedules, fetch_user_collections, parse_tags, tag_schedule};
 
 // Custom deserializer to handle duration as either string or integer
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -28,8 +30,11 @@ struct LegacyEntry {
     url: String,
     title: String,
     description: Option<String>,
-    #[serde(deserialize_with = "deserialize_duration")]
+    /// Missing or invalid schedules fall back to the tags' default schedule,
+    /// then the user's defaults
+    #[serde(default, deserialize_with = "deserialize_duration")]
     duration: String,
+    #[serde(default)]
     interval: String,
     visited: Option<i64>,
     #[serde(rename = "id")]
@@ -42,6 +47,10 @@ struct LegacyEntry {
     dismissed_at: Option<String>,
     #[serde(default)]
     tags: Vec<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    pinned: bool,
 }
 
 pub async fn import_data(pool: &SqlitePool, file_path: &str, user_id: &str) -> Result<(), Box<dyn std::error::Error>> {
@@ -58,70 +67,100 @@ pub async fn import_data(pool: &SqlitePool, file_path: &str, user_id: &str) -> R
     let content = fs::read_to_string(file_path)?;
     let entries: Vec<LegacyEntry> = serde_json::from_str(&content)?;
 
+    let settings = UserSettings::load(pool, user_id).await?;
+    let collections = fetch_user_collections(pool, user_id).await;
+    let collection_id = settings
+        .default_collection_id
+        .as_ref()
+        .filter(|id| collections.iter().any(|c| &c.id == *id));
+    let default_tags = parse_tags(Some(&settings.default_tags));
+    let namespace = TagNamespace::for_entry(user_id, collection_id.map(|s| s.as_str()));
+    let tag_schedules = fetch_tag_schedules(pool, &namespace).await?;
+
     let now = chrono::Utc::now().to_rfc3339();
     let mut imported = 0;
     let mut tx = pool.begin().await?;
 
     for entry in entries {
         let id = Uuid::new_v4().to_string();
-        let duration: i64 = entry.duration.parse().unwrap_or(1);
         let created_at = entry.created_at.unwrap_or_else(|| now.clone());
         let updated_at = entry.updated_at.unwrap_or_else(|| now.clone());
 
-        let interval = match entry.interval.as_str() {
-            "hours" => Interval::Hours,
-            "days" => Interval::Days,
-            "weeks" => Interval::Weeks,
-            "months" => Interval::Months,
-            "years" => Interval::Years,
-            other => {
-                eprintln!("Unknown interval: {other}, defaulting to days");
-                Interval::Days
-            }
+        // Untagged entries get the user's default tags. Imported tags are
+        // normalized the same way as the entry form's.
+        let tags = if entry.tags.is_empty() {
+            default_tags.clone()
+        } else {
+            parse_tags(Some(&entry.tags.join(",")))
+        };
+
+        let parsed_interval = entry.interval.parse::<Interval>();
+        if parsed_interval.is_err() && !entry.interval.is_empty() {
+            eprintln!("Unknown interval: {}", entry.interval);
+        }
+        // A duration under 1 is treated like one that doesn't parse
+        let parsed_duration = entry.duration.parse::<i64>().ok().filter(|d| *d >= 1);
+        let (duration, interval) = match (parsed_duration, parsed_interval) {
+            (Some(duration), Ok(interval)) => (duration, interval),
+            (duration, interval) => tag_schedule(&tag_schedules, &tags).unwrap_or((
+                duration.unwrap_or(settings.default_duration),
+                interval.unwrap_or(settings.default_interval),
+            )),
+        };
+
+        let priority = match entry.priority.as_deref() {
+            None => Priority::default(),
+            Some(p) => p.parse::<Priority>().unwrap_or_else(|_| {
+                eprintln!("Unknown priority: {p}, defaulting to normal");
+                Priority::default()
+            }),
         };
 
         sqlx::query(
             r#"
-            INSERT INTO entries (id, user_id, url, title, description, duration, interval, dismissed_at, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO entries (id, user_id, collection_id, url, title, description, duration, interval, priority, pinned, dismissed_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(&id)
         .bind(user_id)
+        .bind(collection_id)
         .bind(&entry.url)
         .bind(&entry.title)
         .bind(&entry.description)
         .bind(duration)
         .bind(&interval)
+        .bind(priority)
+        .bind(entry.pinned)
         .bind(&entry.dismissed_at)
         .bind(&created_at)
         .bind(&updated_at)
         .execute(&mut *tx)
         .await?;
 
-        // Handle tags
-        for tag_name in &entry.tags {
-            let tag_name = tag_name.trim().to_lowercase();
-            if tag_name.is_empty() {
-                continue;
-            }
-
-            let tag_id: Option<(String,)> = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
-                .bind(&tag_name)
-                .fetch_optional(&mut *tx)
-                .await?;
+        for tag_name in tags {
+            let tag_id: Option<(String,)> = sqlx::query_as(
+                "SELECT id FROM tags WHERE name = ? AND user_id IS ? AND collection_id IS ?"
+            )
+            .bind(&tag_name)
+            .bind(namespace.user_id())
+            .bind(namespace.collection_id())
+            .fetch_optional(&mut *tx)
+            .await?;
 
             let tag_id = match tag_id {
                 Some((id,)) => id,
                 None => {
-                    let new_id = Uuid::new_v4().to_string();
-                    sqlx::query("INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)")
-                        .bind(&new_id)
-                        .bind(&tag_name)
-                        .bind(&now)
+                    let tag = Tag::new(tag_name, &namespace);
+                    sqlx::query("INSERT INTO tags (id, name, user_id, collection_id, created_at) VALUES (?, ?, ?, ?, ?)")
+                        .bind(&tag.id)
+                        .bind(&tag.name)
+                        .bind(&tag.user_id)
+                        .bind(&tag.collection_id)
+                        .bind(&tag.created_at)
                         .execute(&mut *tx)
                         .await?;
-                    new_id
+                    tag.id
                 }
             };
 
@@ -156,6 +195,29 @@ pub async fn import_data(pool: &SqlitePool, file_path: &str, user_id: &str) -> R
     Ok(())
 }
 
+/// Create a new collection owned by `user_id` from a collection export
+pub async fn import_collection(pool: &SqlitePool, file_path: &str, user_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let user_exists: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    if user_exists.0 == 0 {
+        return Err(format!("User with ID '{}' not found", user_id).into());
+    }
+
+    let content = fs::read_to_string(file_path)?;
+    let bundle = parse_collection_bundle(&content)?;
+    let collection = import_collection_bundle(pool, user_id, bundle, None).await?;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entries WHERE collection_id = ?")
+        .bind(&collection.id)
+        .fetch_one(pool)
+        .await?;
+    println!("Imported {} entries into {} ({})", count, collection.name, collection.id);
+    Ok(())
+}
+
 pub async fn create_user(pool: &SqlitePool, name: &str, email: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
     let id = Uuid::new_v4().to_string();
     let invite_code = Uuid::new_v4().to_string();
@@ -180,3 +242,41 @@ pub async fn create_user(pool: &SqlitePool, name: &str, email: Option<&str>) ->
 
     Ok(())
 }
+
+/// Delete a user and everything they own. Their collections pass to another
+/// member (see migration 018); collections nobody else is in are deleted.
+pub async fn delete_user(pool: &SqlitePool, user_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let user: Option<(String,)> = sqlx::query_as("SELECT name FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some((name,)) = user else {
+        return Err(format!("User with ID '{}' not found", user_id).into());
+    };
+
+    let owned: Vec<(String, String)> = sqlx::query_as("SELECT id, name FROM collections WHERE owner_id = ? ORDER BY name")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    println!("Deleted user {}", name);
+    for (collection_id, collection_name) in owned {
+        let new_owner: Option<(String,)> = sqlx::query_as(
+            "SELECT u.name FROM collections c JOIN users u ON u.id = c.owner_id WHERE c.id = ?"
+        )
+        .bind(&collection_id)
+        .fetch_optional(pool)
+        .await?;
+        match new_owner {
+            Some((owner,)) => println!("  {}: now owned by {}", collection_name, owner),
+            None => println!("  {}: deleted (no other members)", collection_name),
+        }
+    }
+
+    Ok(())
+}
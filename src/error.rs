@@ -7,12 +7,15 @@ pub enum AppError {
     Template(askama::Error),
     Session(tower_sessions::session::Error),
     NotFound,
+    /// A request the forms would never send, e.g. a note over the length limit
+    BadRequest(String),
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         match self {
             AppError::NotFound => (StatusCode::NOT_FOUND, "Not found").into_response(),
+            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
             AppError::Database(e) => {
                 tracing::error!("Database error: {e}");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
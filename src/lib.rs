@@ -2,8 +2,10 @@ pub mod auth;
 pub mod cli;
 pub mod db;
 pub mod error;
+pub mod markdown;
 pub mod models;
 pub mod routes;
+pub mod tag_filter;
 
 pub const STATIC_HASH: &str = env!("STATIC_HASH");
 
@@ -57,6 +59,9 @@ pub async fn build_app(pool: SqlitePool, secure_cookies: bool) -> Router {
         .merge(routes::collections::router())
         .merge(routes::export::router())
         .merge(routes::tags::router())
+        .merge(routes::settings::router())
+        .merge(routes::shared::router())
+        .merge(routes::comments::router())
         .nest_service(
             "/static",
             ServiceBuilder::new()
@@ -28,6 +28,17 @@ async fn main() {
                 }
                 return;
             }
+            "import-collection" => {
+                if args.len() < 4 {
+                    eprintln!("Usage: interne import-collection <file.json> <user_id>");
+                    std::process::exit(1);
+                }
+                if let Err(e) = interne::cli::import_collection(&pool, &args[2], &args[3]).await {
+                    eprintln!("Import failed: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            }
             "create-user" => {
                 if args.len() < 3 {
                     eprintln!("Usage: interne create-user <name> [email]");
@@ -40,6 +51,17 @@ async fn main() {
                 }
                 return;
             }
+            "delete-user" => {
+                if args.len() < 3 {
+                    eprintln!("Usage: interne delete-user <user_id>");
+                    std::process::exit(1);
+                }
+                if let Err(e) = interne::cli::delete_user(&pool, &args[2]).await {
+                    eprintln!("Failed to delete user: {}", e);
+                    std::process::exit(1);
+                }
+                return;
+            }
             "help" | "--help" | "-h" => {
                 println!("Interne - Spaced repetition for websites");
                 println!();
@@ -48,7 +70,10 @@ async fn main() {
                 println!("Commands:");
                 println!("  (none)              Start the web server");
                 println!("  create-user <name>  Create a new user");
+                println!("  delete-user <id>    Delete a user, passing their collections on");
                 println!("  import <file> <id>  Import legacy JSON data");
+                println!("  import-collection <file> <id>");
+                println!("                      Import a collection export as a new collection");
                 println!("  help                Show this help");
                 return;
             }
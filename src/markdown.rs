@@ -0,0 +1,56 @@
+use pulldown_cmark::{html, Options, Parser};
+
+/// Render user-supplied Markdown to HTML that is safe to embed in a page.
+///
+/// The generated HTML is passed through ammonia, which strips scripts, event
+/// handlers and other unsafe markup, and adds `rel="noopener noreferrer"` to links.
+pub fn render_markdown(source: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+
+    let parser = Parser::new_ext(source, options);
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+
+    ammonia::clean(&unsafe_html)
+}
+
+/// Render an optional Markdown field, treating blank input as absent.
+pub fn render_optional(source: Option<&str>) -> Option<String> {
+    source
+        .filter(|s| !s.trim().is_empty())
+        .map(render_markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_basic_markdown() {
+        let html = render_markdown("Some *emphasis* and a [link](https://example.com)");
+        assert!(html.contains("<em>emphasis</em>"));
+        assert!(html.contains("href=\"https://example.com\""));
+    }
+
+    #[test]
+    fn strips_script_tags() {
+        let html = render_markdown("hello <script>alert(1)</script>");
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("hello"));
+    }
+
+    #[test]
+    fn strips_event_handlers() {
+        let html = render_markdown("<img src=\"x.png\" onerror=\"alert(1)\">");
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn blank_optional_is_none() {
+        assert!(render_optional(None).is_none());
+        assert!(render_optional(Some("   ")).is_none());
+        assert!(render_optional(Some("text")).is_some());
+    }
+}
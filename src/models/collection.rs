@@ -1,8 +1,10 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, SqlitePool};
 use uuid::Uuid;
 
+use super::Interval;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Collection {
     pub id: String,
@@ -11,6 +13,15 @@ pub struct Collection {
     pub invite_code: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Schedule for new entries added to the collection without one of their own
+    pub default_duration: Option<i64>,
+    pub default_interval: Option<Interval>,
+    /// Comma-separated tags added to every new entry in the collection
+    pub default_tags: String,
+    /// Token for the public read-only page at `/shared/{token}`, if shared
+    pub share_token: Option<String>,
+    /// The collection this one is nested under; its members can see this one too
+    pub parent_id: Option<String>,
 }
 
 impl Collection {
@@ -23,6 +34,94 @@ impl Collection {
             invite_code: Uuid::new_v4().to_string(),
             created_at: now.clone(),
             updated_at: now,
+            default_duration: None,
+            default_interval: None,
+            default_tags: String::new(),
+            share_token: None,
+            parent_id: None,
+        }
+    }
+
+    /// The collection's default schedule, if it has one
+    pub fn schedule(&self) -> Option<(i64, Interval)> {
+        Some((self.default_duration?, self.default_interval?))
+    }
+}
+
+/// What a user may do in a collection. Variants are declared from least to
+/// most access so roles can be compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+pub enum CollectionRole {
+    /// Read and visit entries
+    #[serde(rename = "viewer")]
+    #[sqlx(rename = "viewer")]
+    Viewer,
+    /// Also add, edit and delete any of the collection's entries
+    #[default]
+    #[serde(rename = "editor")]
+    #[sqlx(rename = "editor")]
+    Editor,
+    /// Also manage members and invites
+    #[serde(rename = "admin")]
+    #[sqlx(rename = "admin")]
+    Admin,
+    /// The collection's `owner_id`; never stored on a member row
+    #[serde(rename = "owner")]
+    #[sqlx(rename = "owner")]
+    Owner,
+}
+
+impl CollectionRole {
+    /// Roles that can be given to a member
+    pub const ASSIGNABLE: [CollectionRole; 3] = [CollectionRole::Viewer, CollectionRole::Editor, CollectionRole::Admin];
+
+    /// The user's role in a collection, or `None` if they aren't in it.
+    /// Includes roles inherited from parent collections.
+    pub async fn load(db: &SqlitePool, collection_id: &str, user_id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let role: Option<(CollectionRole,)> =
+            sqlx::query_as("SELECT role FROM collection_access WHERE collection_id = ? AND user_id = ?")
+                .bind(collection_id)
+                .bind(user_id)
+                .fetch_optional(db)
+                .await?;
+
+        Ok(role.map(|(role,)| role))
+    }
+
+    /// Name shown in the UI
+    pub fn label(self) -> &'static str {
+        match self {
+            CollectionRole::Viewer => "Viewer",
+            CollectionRole::Editor => "Editor",
+            CollectionRole::Admin => "Admin",
+            CollectionRole::Owner => "Owner",
+        }
+    }
+
+    pub fn can_edit_entries(self) -> bool {
+        self >= CollectionRole::Editor
+    }
+
+    pub fn can_manage_members(self) -> bool {
+        self >= CollectionRole::Admin
+    }
+
+    /// Whether someone with this role may give a member `other`'s role, or
+    /// change or remove a member who has it: admins manage viewers and
+    /// editors, and only the owner manages admins
+    pub fn outranks(self, other: CollectionRole) -> bool {
+        self.can_manage_members() && self > other
+    }
+}
+
+impl std::fmt::Display for CollectionRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollectionRole::Viewer => write!(f, "viewer"),
+            CollectionRole::Editor => write!(f, "editor"),
+            CollectionRole::Admin => write!(f, "admin"),
+            CollectionRole::Owner => write!(f, "owner"),
         }
     }
 }
@@ -32,14 +131,119 @@ pub struct CollectionMember {
     pub collection_id: String,
     pub user_id: String,
     pub joined_at: String,
+    pub role: CollectionRole,
 }
 
 impl CollectionMember {
-    pub fn new(collection_id: String, user_id: String) -> Self {
+    pub fn new(collection_id: String, user_id: String, role: CollectionRole) -> Self {
         Self {
             collection_id,
             user_id,
             joined_at: Utc::now().to_rfc3339(),
+            role,
         }
     }
 }
+
+/// A link for joining a collection. A collection can have several; each can
+/// expire or run out of uses independently.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CollectionInvite {
+    pub id: String,
+    pub collection_id: String,
+    pub code: String,
+    /// Who the invite was made for, shown to the collection's admins
+    pub label: Option<String>,
+    /// Role given to users who join with this invite
+    pub role: CollectionRole,
+    /// `None` for unlimited uses
+    pub max_uses: Option<i64>,
+    pub use_count: i64,
+    /// `None` for an invite that never expires
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+impl CollectionInvite {
+    pub fn new(
+        collection_id: String,
+        code: String,
+        label: Option<String>,
+        role: CollectionRole,
+        max_uses: Option<i64>,
+        expires_at: Option<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            collection_id,
+            code,
+            label,
+            role,
+            max_uses,
+            use_count: 0,
+            expires_at,
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|at| at.parse::<DateTime<Utc>>().ok())
+            .is_some_and(|at| at <= now)
+    }
+
+    pub fn is_used_up(&self) -> bool {
+        self.max_uses.is_some_and(|max| self.use_count >= max)
+    }
+
+    /// Why the invite can't be used to join, if it can't
+    pub fn unusable_reason(&self, now: DateTime<Utc>) -> Option<&'static str> {
+        if self.is_expired(now) {
+            Some("This invite has expired")
+        } else if self.is_used_up() {
+            Some("This invite has no uses left")
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_are_ordered_by_access() {
+        assert!(!CollectionRole::Viewer.can_edit_entries());
+        assert!(CollectionRole::Editor.can_edit_entries());
+        assert!(!CollectionRole::Editor.can_manage_members());
+        assert!(CollectionRole::Admin.can_manage_members());
+        assert!(CollectionRole::Owner.can_manage_members());
+    }
+
+    fn invite(max_uses: Option<i64>, use_count: i64, expires_at: Option<&str>) -> CollectionInvite {
+        let mut invite = CollectionInvite::new(
+            "c".to_string(),
+            "code".to_string(),
+            None,
+            CollectionRole::Editor,
+            max_uses,
+            expires_at.map(str::to_string),
+        );
+        invite.use_count = use_count;
+        invite
+    }
+
+    #[test]
+    fn invite_limits() {
+        let now: DateTime<Utc> = "2024-06-01T12:00:00+00:00".parse().unwrap();
+        assert_eq!(invite(None, 100, None).unusable_reason(now), None);
+        assert_eq!(invite(Some(3), 2, Some("2024-06-02T00:00:00+00:00")).unusable_reason(now), None);
+        assert_eq!(invite(Some(3), 3, None).unusable_reason(now), Some("This invite has no uses left"));
+        assert_eq!(
+            invite(None, 0, Some("2024-06-01T12:00:00+00:00")).unusable_reason(now),
+            Some("This invite has expired")
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// A comment on a collection entry, optionally replying to another comment
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Comment {
+    pub id: String,
+    pub entry_id: String,
+    /// `None` once the author's account is deleted
+    pub user_id: Option<String>,
+    pub parent_id: Option<String>,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: String,
+    pub deleted_at: Option<String>,
+}
+
+impl Comment {
+    pub fn new(entry_id: String, user_id: String, parent_id: Option<String>, body: String) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            entry_id,
+            user_id: Some(user_id),
+            parent_id,
+            body,
+            created_at: now.clone(),
+            updated_at: now,
+            deleted_at: None,
+        }
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+}
@@ -33,6 +33,61 @@ impl std::fmt::Display for Interval {
     }
 }
 
+impl std::str::FromStr for Interval {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hours" => Ok(Interval::Hours),
+            "days" => Ok(Interval::Days),
+            "weeks" => Ok(Interval::Weeks),
+            "months" => Ok(Interval::Months),
+            "years" => Ok(Interval::Years),
+            other => Err(format!("Unknown interval: {other}")),
+        }
+    }
+}
+
+/// How urgently an entry should be revisited once it's due. Variants are
+/// declared low to high so the derived ordering ranks them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+pub enum Priority {
+    #[serde(rename = "low")]
+    #[sqlx(rename = "low")]
+    Low,
+    #[default]
+    #[serde(rename = "normal")]
+    #[sqlx(rename = "normal")]
+    Normal,
+    #[serde(rename = "high")]
+    #[sqlx(rename = "high")]
+    High,
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+        }
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            other => Err(format!("Unknown priority: {other}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Entry {
     pub id: String,
@@ -46,6 +101,8 @@ pub struct Entry {
     pub dismissed_at: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub priority: Priority,
+    pub pinned: bool,
 }
 
 #[cfg(test)]
@@ -69,6 +126,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn interval_from_str_roundtrip() {
+        for variant in [Interval::Hours, Interval::Days, Interval::Weeks, Interval::Months, Interval::Years] {
+            assert_eq!(variant.to_string().parse::<Interval>().unwrap(), variant);
+        }
+        assert!("fortnights".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn priority_orders_low_to_high() {
+        assert!(Priority::Low < Priority::Normal);
+        assert!(Priority::Normal < Priority::High);
+        assert_eq!(Priority::default(), Priority::Normal);
+    }
+
+    #[test]
+    fn priority_from_str_roundtrip() {
+        for variant in [Priority::Low, Priority::Normal, Priority::High] {
+            assert_eq!(variant.to_string().parse::<Priority>().unwrap(), variant);
+        }
+        assert!("urgent".parse::<Priority>().is_err());
+    }
+
     #[test]
     fn interval_display() {
         assert_eq!(Interval::Hours.to_string(), "hours");
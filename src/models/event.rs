@@ -0,0 +1,96 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteExecutor};
+use uuid::Uuid;
+
+use super::Entry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "TEXT", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    EntryAdded,
+    EntryEdited,
+    EntryRemoved,
+    Visited,
+    MemberJoined,
+    MemberLeft,
+    /// `subject` is the member who was removed; `user_id` removed them
+    MemberRemoved,
+    /// `subject` is the new owner; `user_id` is the previous one
+    OwnershipTransferred,
+}
+
+impl EventKind {
+    /// Verb shown after the user's name in the activity feed
+    pub fn verb(self) -> &'static str {
+        match self {
+            EventKind::EntryAdded => "added",
+            EventKind::EntryEdited => "edited",
+            EventKind::EntryRemoved => "removed",
+            EventKind::Visited => "visited",
+            EventKind::MemberJoined => "joined the collection",
+            EventKind::MemberLeft => "left the collection",
+            EventKind::MemberRemoved => "removed",
+            EventKind::OwnershipTransferred => "handed ownership to",
+        }
+    }
+}
+
+/// Something a user did in a collection, shown in its activity feed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CollectionEvent {
+    pub id: String,
+    pub collection_id: String,
+    pub user_id: Option<String>,
+    pub kind: EventKind,
+    pub entry_id: Option<String>,
+    /// Entry title or member name at the time of the event
+    pub subject: Option<String>,
+    pub created_at: String,
+}
+
+impl CollectionEvent {
+    pub fn new(collection_id: String, user_id: String, kind: EventKind) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            collection_id,
+            user_id: Some(user_id),
+            kind,
+            entry_id: None,
+            subject: None,
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn about_entry(mut self, entry_id: &str, title: &str) -> Self {
+        self.entry_id = Some(entry_id.to_string());
+        self.subject = Some(title.to_string());
+        self
+    }
+
+    /// An event about an entry, or `None` if the entry isn't in a collection
+    pub fn for_entry(kind: EventKind, user_id: &str, entry: &Entry) -> Option<Self> {
+        let event = Self::new(entry.collection_id.clone()?, user_id.to_string(), kind);
+        Some(event.about_entry(&entry.id, &entry.title))
+    }
+
+    pub async fn record(&self, db: impl SqliteExecutor<'_>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO collection_events (id, collection_id, user_id, kind, entry_id, subject, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&self.id)
+        .bind(&self.collection_id)
+        .bind(&self.user_id)
+        .bind(self.kind)
+        .bind(&self.entry_id)
+        .bind(&self.subject)
+        .bind(&self.created_at)
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
@@ -3,8 +3,18 @@ pub mod entry;
 pub mod collection;
 
 pub mod visit;
+pub mod revision;
+pub mod settings;
+pub mod tag;
+pub mod event;
+pub mod comment;
 
 pub use user::User;
-pub use entry::{Entry, Interval};
-pub use collection::{Collection, CollectionMember};
+pub use entry::{Entry, Interval, Priority};
+pub use collection::{Collection, CollectionInvite, CollectionMember, CollectionRole};
 pub use visit::Visit;
+pub use revision::{EntryRevision, EntrySnapshot, FieldChange};
+pub use settings::UserSettings;
+pub use tag::{Tag, TagNamespace};
+pub use event::{CollectionEvent, EventKind};
+pub use comment::Comment;
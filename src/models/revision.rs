@@ -0,0 +1,207 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::{Entry, Interval, Priority};
+
+/// A single field that changed in an edit. Values are stored as strings so
+/// every field can share one representation; `None` means the field was empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EntryRevision {
+    pub id: String,
+    pub entry_id: String,
+    pub user_id: Option<String>,
+    /// JSON-encoded `Vec<FieldChange>`
+    pub changes: String,
+    pub created_at: String,
+}
+
+impl EntryRevision {
+    pub fn new(entry_id: String, user_id: String, changes: &[FieldChange]) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            entry_id,
+            user_id: Some(user_id),
+            changes: serde_json::to_string(changes).expect("field changes serialize"),
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub fn field_changes(&self) -> Vec<FieldChange> {
+        serde_json::from_str(&self.changes).unwrap_or_default()
+    }
+}
+
+/// The user-editable state of an entry, used to diff and revert edits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntrySnapshot {
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub duration: i64,
+    pub interval: Interval,
+    pub collection_id: Option<String>,
+    pub priority: Priority,
+    pub pinned: bool,
+    /// Tag names, sorted so that reordering alone is not recorded as a change
+    pub tags: Vec<String>,
+}
+
+impl EntrySnapshot {
+    pub fn new(entry: &Entry, mut tags: Vec<String>) -> Self {
+        tags.sort();
+        Self {
+            url: entry.url.clone(),
+            title: entry.title.clone(),
+            description: entry.description.clone().filter(|d| !d.is_empty()),
+            duration: entry.duration,
+            interval: entry.interval,
+            collection_id: entry.collection_id.clone(),
+            priority: entry.priority,
+            pinned: entry.pinned,
+            tags,
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("url", Some(self.url.clone())),
+            ("title", Some(self.title.clone())),
+            ("description", self.description.clone()),
+            ("duration", Some(self.duration.to_string())),
+            ("interval", Some(self.interval.to_string())),
+            ("collection_id", self.collection_id.clone()),
+            ("priority", Some(self.priority.to_string())),
+            ("pinned", Some(self.pinned.to_string())),
+            ("tags", Some(self.tags.join(", ")).filter(|t| !t.is_empty())),
+        ]
+    }
+
+    /// Field-level differences going from `self` to `other`
+    pub fn diff(&self, other: &EntrySnapshot) -> Vec<FieldChange> {
+        self.fields()
+            .into_iter()
+            .zip(other.fields())
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| FieldChange {
+                field: field.to_string(),
+                old,
+                new,
+            })
+            .collect()
+    }
+
+    /// Undo a set of changes, restoring each field to its `old` value.
+    /// Values that no longer parse (e.g. a corrupt duration) are left as they are.
+    pub fn undo(&mut self, changes: &[FieldChange]) {
+        for change in changes {
+            let old = change.old.clone();
+            match change.field.as_str() {
+                "url" => self.url = old.unwrap_or_default(),
+                "title" => self.title = old.unwrap_or_default(),
+                "description" => self.description = old,
+                "duration" => {
+                    if let Some(d) = old.and_then(|d| d.parse().ok()) {
+                        self.duration = d;
+                    }
+                }
+                "interval" => {
+                    if let Some(i) = old.and_then(|i| i.parse().ok()) {
+                        self.interval = i;
+                    }
+                }
+                "collection_id" => self.collection_id = old,
+                "priority" => {
+                    if let Some(p) = old.and_then(|p| p.parse().ok()) {
+                        self.priority = p;
+                    }
+                }
+                "pinned" => {
+                    if let Some(p) = old.and_then(|p| p.parse().ok()) {
+                        self.pinned = p;
+                    }
+                }
+                "tags" => {
+                    self.tags = old
+                        .map(|t| t.split(", ").map(|s| s.to_string()).collect())
+                        .unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_snapshot() -> EntrySnapshot {
+        EntrySnapshot {
+            url: "https://example.com/".to_string(),
+            title: "Original".to_string(),
+            description: None,
+            duration: 3,
+            interval: Interval::Days,
+            collection_id: None,
+            priority: Priority::Normal,
+            pinned: false,
+            tags: vec!["rust".to_string()],
+        }
+    }
+
+    #[test]
+    fn diff_identical_is_empty() {
+        let snapshot = make_snapshot();
+        assert!(snapshot.diff(&snapshot.clone()).is_empty());
+    }
+
+    #[test]
+    fn diff_records_changed_fields_only() {
+        let old = make_snapshot();
+        let mut new = old.clone();
+        new.title = "Renamed".to_string();
+        new.interval = Interval::Weeks;
+
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].field, "title");
+        assert_eq!(changes[0].old.as_deref(), Some("Original"));
+        assert_eq!(changes[0].new.as_deref(), Some("Renamed"));
+        assert_eq!(changes[1].field, "interval");
+    }
+
+    #[test]
+    fn undo_restores_previous_state() {
+        let old = make_snapshot();
+        let mut new = old.clone();
+        new.description = Some("Added".to_string());
+        new.duration = 10;
+        new.priority = Priority::High;
+        new.pinned = true;
+        new.tags = vec!["rust".to_string(), "web".to_string()];
+
+        let changes = old.diff(&new);
+        new.undo(&changes);
+        assert_eq!(new, old);
+    }
+
+    #[test]
+    fn undo_clears_tags() {
+        let mut old = make_snapshot();
+        old.tags = vec![];
+        let new = make_snapshot();
+
+        let mut reverted = new.clone();
+        reverted.undo(&old.diff(&new));
+        assert!(reverted.tags.is_empty());
+    }
+}
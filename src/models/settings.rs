@@ -0,0 +1,40 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+
+use super::Interval;
+
+/// Per-user defaults applied to new entries. Users without a saved row get
+/// [`UserSettings::defaults`], matching a blank new entry form.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserSettings {
+    pub user_id: String,
+    pub default_duration: i64,
+    pub default_interval: Interval,
+    pub default_collection_id: Option<String>,
+    /// Comma-separated, as typed in the tags field
+    pub default_tags: String,
+    pub updated_at: String,
+}
+
+impl UserSettings {
+    pub fn defaults(user_id: String) -> Self {
+        Self {
+            user_id,
+            default_duration: 1,
+            default_interval: Interval::Days,
+            default_collection_id: None,
+            default_tags: String::new(),
+            updated_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    pub async fn load(db: &SqlitePool, user_id: &str) -> Result<Self, sqlx::Error> {
+        let settings: Option<UserSettings> = sqlx::query_as("SELECT * FROM user_settings WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?;
+
+        Ok(settings.unwrap_or_else(|| Self::defaults(user_id.to_string())))
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::Interval;
+
+/// A tag name within one namespace. Exactly one of `user_id` (a private tag)
+/// and `collection_id` (a tag shared by a collection's entries) is set.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub user_id: Option<String>,
+    pub collection_id: Option<String>,
+    pub created_at: String,
+    /// Schedule for new entries added with this tag and no schedule of their own
+    pub default_duration: Option<i64>,
+    pub default_interval: Option<Interval>,
+}
+
+/// Where an entry's tags live: the owner's own tags for a private entry,
+/// or the collection's tags for a shared one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagNamespace {
+    User(String),
+    Collection(String),
+}
+
+impl TagNamespace {
+    pub fn for_entry(user_id: &str, collection_id: Option<&str>) -> Self {
+        match collection_id {
+            Some(collection_id) => TagNamespace::Collection(collection_id.to_string()),
+            None => TagNamespace::User(user_id.to_string()),
+        }
+    }
+
+    pub fn user_id(&self) -> Option<&str> {
+        match self {
+            TagNamespace::User(id) => Some(id),
+            TagNamespace::Collection(_) => None,
+        }
+    }
+
+    pub fn collection_id(&self) -> Option<&str> {
+        match self {
+            TagNamespace::User(_) => None,
+            TagNamespace::Collection(id) => Some(id),
+        }
+    }
+}
+
+impl Tag {
+    pub fn new(name: String, namespace: &TagNamespace) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            user_id: namespace.user_id().map(|s| s.to_string()),
+            collection_id: namespace.collection_id().map(|s| s.to_string()),
+            created_at: Utc::now().to_rfc3339(),
+            default_duration: None,
+            default_interval: None,
+        }
+    }
+
+    /// The tag's default schedule, if it has one
+    pub fn schedule(&self) -> Option<(i64, Interval)> {
+        Some((self.default_duration?, self.default_interval?))
+    }
+
+    pub fn namespace(&self) -> TagNamespace {
+        match &self.collection_id {
+            Some(collection_id) => TagNamespace::Collection(collection_id.clone()),
+            None => TagNamespace::User(self.user_id.clone().unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_entry_uses_owner_namespace() {
+        let ns = TagNamespace::for_entry("user-1", None);
+        assert_eq!(ns, TagNamespace::User("user-1".to_string()));
+        assert_eq!(ns.user_id(), Some("user-1"));
+        assert_eq!(ns.collection_id(), None);
+    }
+
+    #[test]
+    fn shared_entry_uses_collection_namespace() {
+        let ns = TagNamespace::for_entry("user-1", Some("col-1"));
+        assert_eq!(ns.user_id(), None);
+        assert_eq!(ns.collection_id(), Some("col-1"));
+
+        let tag = Tag::new("rust".to_string(), &ns);
+        assert_eq!(tag.user_id, None);
+        assert_eq!(tag.collection_id.as_deref(), Some("col-1"));
+        assert_eq!(tag.namespace(), ns);
+    }
+}
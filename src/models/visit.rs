@@ -9,6 +9,7 @@ pub struct Visit {
     pub entry_id: String,
     pub user_id: String,
     pub visited_at: String,
+    pub note: Option<String>,
 }
 
 impl Visit {
@@ -18,6 +19,7 @@ impl Visit {
             entry_id,
             user_id,
             visited_at: Utc::now().to_rfc3339(),
+            note: None,
         }
     }
 }
@@ -1,23 +1,31 @@
 use askama::Template;
 use axum::{
-    extract::{Path, State},
-    response::{Html, IntoResponse, Redirect},
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect, Response},
     routing::{delete, get, post},
     Form, Router,
 };
+use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use sqlx::FromRow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::auth::AuthUser;
 use crate::error::AppError;
-use crate::models::{Collection, CollectionMember, User};
+use crate::models::{
+    Collection, CollectionEvent, CollectionInvite, CollectionMember, CollectionRole, Entry, EventKind, Interval, User,
+};
+use crate::routes::comments::fill_comment_counts;
+use crate::routes::entries::{
+    build_entry_view, calculate_availability, format_last_viewed, parse_tags, reschedule_collection_entries, EntryView, EntryWithCount,
+};
 use crate::AppState;
 
 #[derive(Template)]
 #[template(path = "collections/list.html")]
 struct CollectionListTemplate {
     collections: Vec<CollectionView>,
+    join_error: Option<String>,
     static_hash: &'static str,
     user: Option<User>,
 }
@@ -26,28 +34,77 @@ struct CollectionListTemplate {
 #[template(path = "collections/form.html")]
 struct CollectionFormTemplate {
     collection: Option<Collection>,
+    /// Collections this one can be nested under
+    parents: Vec<CollectionOption>,
+    parent_id: String,
     errors: HashMap<String, String>,
     static_hash: &'static str,
     user: Option<User>,
 }
 
+impl CollectionFormTemplate {
+    /// Interval shown in the form; days if there's no default schedule yet
+    fn interval(&self) -> String {
+        let interval = self.collection.as_ref().and_then(|c| c.default_interval);
+        interval.unwrap_or(Interval::Days).to_string()
+    }
+}
+
 #[derive(Template)]
 #[template(path = "collections/show.html")]
 struct CollectionShowTemplate {
     collection: Collection,
-    members: Vec<User>,
+    /// The parents the user can see, outermost first
+    ancestors: Vec<Collection>,
+    subcollections: Vec<Collection>,
+    owner_name: String,
+    members: Vec<MemberWithRole>,
+    entries: Vec<EntryView>,
+    /// Only loaded for users who can manage members
+    invites: Vec<InviteView>,
+    errors: HashMap<String, String>,
     is_owner: bool,
+    /// The viewing user's role
+    role: CollectionRole,
+    /// Roles the viewing user may give out
+    roles: Vec<CollectionRole>,
     static_hash: &'static str,
     user: Option<User>,
 }
 
+impl CollectionShowTemplate {
+    /// Interval preselected for rescheduling; the collection's default, else days
+    fn interval(&self) -> String {
+        self.collection.default_interval.unwrap_or(Interval::Days).to_string()
+    }
+}
+
 struct CollectionView {
     id: String,
     name: String,
+    /// Nesting depth, capped so deep trees don't run off the page
+    depth: usize,
     is_owner: bool,
+    role: CollectionRole,
     member_count: i64,
 }
 
+/// A member of a collection and their role in it
+#[derive(FromRow)]
+struct MemberWithRole {
+    #[sqlx(flatten)]
+    user: User,
+    role: CollectionRole,
+}
+
+/// Collection with the viewing user's role in it
+#[derive(FromRow)]
+struct CollectionWithRole {
+    #[sqlx(flatten)]
+    collection: Collection,
+    role: CollectionRole,
+}
+
 /// Collection with member count for queries that join with collection_members
 #[derive(FromRow)]
 struct CollectionWithCount {
@@ -58,12 +115,18 @@ struct CollectionWithCount {
     invite_code: String,
     created_at: String,
     updated_at: String,
-    // Extra field
+    default_duration: Option<i64>,
+    default_interval: Option<Interval>,
+    default_tags: String,
+    share_token: Option<String>,
+    parent_id: Option<String>,
+    // Extra fields
     member_count: i64,
+    role: CollectionRole,
 }
 
 impl CollectionWithCount {
-    fn into_collection_and_count(self) -> (Collection, i64) {
+    fn into_collection_and_count(self) -> (Collection, i64, CollectionRole) {
         let collection = Collection {
             id: self.id,
             owner_id: self.owner_id,
@@ -71,14 +134,49 @@ impl CollectionWithCount {
             invite_code: self.invite_code,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            default_duration: self.default_duration,
+            default_interval: self.default_interval,
+            default_tags: self.default_tags,
+            share_token: self.share_token,
+            parent_id: self.parent_id,
         };
-        (collection, self.member_count)
+        (collection, self.member_count, self.role)
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct CollectionForm {
     name: String,
+    /// Blank for no default schedule
+    #[serde(default)]
+    default_duration: String,
+    default_interval: Option<Interval>,
+    #[serde(default)]
+    default_tags: String,
+    /// Blank for a top-level collection
+    parent_id: Option<String>,
+}
+
+impl CollectionForm {
+    /// The default schedule to store; only call once the form has validated
+    fn schedule(&self) -> Option<(i64, Interval)> {
+        let duration = parse_optional_count(&self.default_duration).ok().flatten()?;
+        Some((duration, self.default_interval.unwrap_or(Interval::Days)))
+    }
+
+    fn tags(&self) -> String {
+        parse_tags(Some(&self.default_tags)).join(", ")
+    }
+
+    fn parent_id(&self) -> Option<&str> {
+        self.parent_id.as_deref().filter(|p| !p.is_empty())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NewCollectionQuery {
+    /// Preselects the parent, for "New subcollection" links
+    parent_id: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -86,6 +184,233 @@ pub struct JoinForm {
     invite_code: String,
 }
 
+#[derive(Deserialize)]
+pub struct RoleForm {
+    role: CollectionRole,
+}
+
+#[derive(Template)]
+#[template(path = "collections/progress.html")]
+struct ProgressTemplate {
+    collection: Collection,
+    members: Vec<ProgressMember>,
+    rows: Vec<ProgressRow>,
+    static_hash: &'static str,
+    user: Option<User>,
+}
+
+/// A column of the progress matrix: the owner or a member, with their totals
+struct ProgressMember {
+    id: String,
+    name: String,
+    /// Entries they have visited at least once
+    read: usize,
+    /// Entries that are due for them: never visited, or visited long enough ago
+    due: usize,
+}
+
+/// A row of the progress matrix: one entry, with a cell per member
+struct ProgressRow {
+    entry_id: String,
+    title: String,
+    cells: Vec<ProgressCell>,
+}
+
+struct ProgressCell {
+    last_visit: Option<String>,
+    due: bool,
+}
+
+/// Work out each member's progress through the entries, given everyone's
+/// latest visit keyed by `(entry_id, user_id)`. Fills in the members' totals.
+fn build_progress(
+    entries: Vec<Entry>,
+    members: &mut [ProgressMember],
+    last_visits: &HashMap<(String, String), String>,
+    now: DateTime<Utc>,
+) -> Vec<ProgressRow> {
+    entries
+        .into_iter()
+        .map(|mut entry| {
+            let cells = members
+                .iter_mut()
+                .map(|member| {
+                    // The entry's schedule, as seen from this member's own last visit
+                    entry.dismissed_at = last_visits.get(&(entry.id.clone(), member.id.clone())).cloned();
+                    let (due, _) = calculate_availability(&entry, now);
+                    if entry.dismissed_at.is_some() {
+                        member.read += 1;
+                    }
+                    if due {
+                        member.due += 1;
+                    }
+                    ProgressCell {
+                        last_visit: format_last_viewed(&entry.dismissed_at, now),
+                        due,
+                    }
+                })
+                .collect();
+            ProgressRow {
+                entry_id: entry.id,
+                title: entry.title,
+                cells,
+            }
+        })
+        .collect()
+}
+
+#[derive(Template)]
+#[template(path = "collections/activity.html")]
+struct ActivityTemplate {
+    collection_id: String,
+    events: Vec<EventView>,
+    /// Member the feed is filtered to; empty for everyone
+    member: String,
+    first_page: bool,
+    /// Cursor for the next page, if there is one
+    next_before: Option<i64>,
+}
+
+/// An event joined with its user's name. `seq` is the row's insertion order,
+/// used to page through the feed.
+#[derive(FromRow)]
+struct EventWithUser {
+    seq: i64,
+    #[sqlx(flatten)]
+    event: CollectionEvent,
+    user_name: Option<String>,
+}
+
+struct EventView {
+    user_name: String,
+    verb: &'static str,
+    /// Link target, while the entry still exists
+    entry_id: Option<String>,
+    subject: Option<String>,
+    when: String,
+}
+
+#[derive(Deserialize)]
+pub struct ActivityQuery {
+    #[serde(default)]
+    member: String,
+    before: Option<i64>,
+}
+
+const ACTIVITY_PAGE_SIZE: i64 = 20;
+
+#[derive(Deserialize)]
+pub struct RescheduleForm {
+    duration: String,
+    interval: Interval,
+}
+
+#[derive(Deserialize)]
+pub struct TransferForm {
+    user_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct InviteForm {
+    label: String,
+    role: CollectionRole,
+    /// Blank for unlimited
+    max_uses: String,
+    /// Blank for never
+    expires_in_days: String,
+}
+
+struct InviteView {
+    id: String,
+    code: String,
+    label: Option<String>,
+    role: CollectionRole,
+    /// e.g. "2 of 5 uses"
+    usage: String,
+    /// e.g. "expires in 3 days"; `None` if it never expires
+    expiry: Option<String>,
+    /// Why the invite no longer works, if it doesn't
+    unusable_reason: Option<&'static str>,
+}
+
+impl InviteView {
+    fn new(invite: CollectionInvite, now: DateTime<Utc>) -> Self {
+        let plural = if invite.use_count == 1 { "" } else { "s" };
+        let usage = match invite.max_uses {
+            Some(max) => format!("{} of {} use{}", invite.use_count, max, if max == 1 { "" } else { "s" }),
+            None => format!("{} use{}", invite.use_count, plural),
+        };
+        Self {
+            unusable_reason: invite.unusable_reason(now),
+            expiry: invite.expires_at.as_deref().map(|at| format_expiry(at, now)),
+            id: invite.id,
+            code: invite.code,
+            label: invite.label,
+            role: invite.role,
+            usage,
+        }
+    }
+}
+
+/// "expires in 3 days", "expires in 5 hours" or "expired"
+fn format_expiry(expires_at: &str, now: DateTime<Utc>) -> String {
+    let Ok(at) = expires_at.parse::<DateTime<Utc>>() else {
+        return "expired".to_string();
+    };
+    let diff = at - now;
+    if diff <= Duration::zero() {
+        "expired".to_string()
+    } else if diff.num_days() > 0 {
+        let d = diff.num_days();
+        if d == 1 {
+            "expires in 1 day".to_string()
+        } else {
+            format!("expires in {} days", d)
+        }
+    } else {
+        let h = diff.num_hours().max(1);
+        if h == 1 {
+            "expires in 1 hour".to_string()
+        } else {
+            format!("expires in {} hours", h)
+        }
+    }
+}
+
+/// Parse an optional positive number; blank means none
+fn parse_optional_count(raw: &str) -> Result<Option<i64>, ()> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+    match raw.parse::<i64>() {
+        Ok(n) if n >= 1 => Ok(Some(n)),
+        _ => Err(()),
+    }
+}
+
+fn validate_invite_form(form: &InviteForm) -> HashMap<String, String> {
+    let mut errors = HashMap::new();
+
+    if form.label.len() > 100 {
+        errors.insert("label".to_string(), "Label must be under 100 characters".to_string());
+    }
+
+    if !CollectionRole::ASSIGNABLE.contains(&form.role) {
+        errors.insert("role".to_string(), "Choose viewer, editor or admin".to_string());
+    }
+
+    if parse_optional_count(&form.max_uses).is_err() {
+        errors.insert("max_uses".to_string(), "Max uses must be at least 1".to_string());
+    }
+
+    if parse_optional_count(&form.expires_in_days).is_err() {
+        errors.insert("expires_in_days".to_string(), "Expiry must be at least 1 day".to_string());
+    }
+
+    errors
+}
+
 fn validate_collection_form(form: &CollectionForm) -> HashMap<String, String> {
     let mut errors = HashMap::new();
 
@@ -97,9 +422,113 @@ fn validate_collection_form(form: &CollectionForm) -> HashMap<String, String> {
         errors.insert("name".to_string(), "Name must be under 100 characters".to_string());
     }
 
+    if parse_optional_count(&form.default_duration).is_err() {
+        errors.insert("default_duration".to_string(), "Duration must be at least 1".to_string());
+    }
+
     errors
 }
 
+/// Deeper subcollections are shown at this indent
+const MAX_DEPTH: usize = 4;
+
+/// Order collections as a tree, pairing each with its depth. Collections
+/// must arrive sorted by name; one whose parent is missing (or not visible
+/// to the user) is shown at the top level.
+fn nest_collections<T>(items: Vec<T>, collection: impl Fn(&T) -> &Collection) -> Vec<(usize, T)> {
+    let ids: HashSet<String> = items.iter().map(|item| collection(item).id.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<T>> = HashMap::new();
+    for item in items {
+        let parent = collection(&item).parent_id.clone().filter(|p| ids.contains(p));
+        children.entry(parent).or_default().push(item);
+    }
+
+    let mut nested = Vec::new();
+    let mut stack: Vec<(usize, T)> =
+        children.remove(&None).unwrap_or_default().into_iter().rev().map(|item| (0, item)).collect();
+    while let Some((depth, item)) = stack.pop() {
+        let below = children.remove(&Some(collection(&item).id.clone())).unwrap_or_default();
+        stack.extend(below.into_iter().rev().map(|child| (depth + 1, child)));
+        nested.push((depth, item));
+    }
+    nested
+}
+
+/// A collection in a picker, labelled to show where it sits in the tree
+pub struct CollectionOption {
+    pub id: String,
+    pub label: String,
+}
+
+/// The collections the user has at least `min_role` in, as a tree
+pub async fn fetch_collection_options(
+    db: &sqlx::SqlitePool,
+    user_id: &str,
+    min_role: CollectionRole,
+) -> Result<Vec<CollectionOption>, sqlx::Error> {
+    let collections: Vec<(Collection, CollectionRole)> = sqlx::query_as::<_, CollectionWithRole>(
+        r#"
+        SELECT c.*, a.role FROM collections c
+        JOIN collection_access a ON a.collection_id = c.id
+        WHERE a.user_id = ?
+        ORDER BY c.name
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|c| (c.collection, c.role))
+    .collect();
+
+    Ok(nest_collections(collections, |(c, _)| c)
+        .into_iter()
+        .filter(|(_, (_, role))| *role >= min_role)
+        .map(|(depth, (c, _))| CollectionOption {
+            label: format!("{}{}", "— ".repeat(depth), c.name),
+            id: c.id,
+        })
+        .collect())
+}
+
+/// The ids of a collection and every collection nested below it
+pub async fn fetch_collection_subtree(db: &sqlx::SqlitePool, collection_id: &str) -> Result<HashSet<String>, sqlx::Error> {
+    let ids: Vec<(String,)> = sqlx::query_as(
+        r#"
+        WITH RECURSIVE subtree(id) AS (
+            SELECT ?
+            UNION
+            SELECT c.id FROM collections c JOIN subtree s ON c.parent_id = s.id
+        )
+        SELECT id FROM subtree
+        "#
+    )
+    .bind(collection_id)
+    .fetch_all(db)
+    .await?;
+    Ok(ids.into_iter().map(|(id,)| id).collect())
+}
+
+/// The parent a collection form asks for, if it's one the user may nest
+/// `collection_id` under: they must manage the parent, and it can't be the
+/// collection itself or one of its subcollections
+async fn validate_parent(
+    db: &sqlx::SqlitePool,
+    user_id: &str,
+    collection_id: Option<&str>,
+    parent_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    if let Some(collection_id) = collection_id
+        && fetch_collection_subtree(db, collection_id).await?.contains(parent_id)
+    {
+        return Ok(Some("A collection can't be inside itself or one of its subcollections".to_string()));
+    }
+    if !can_manage_members(db, parent_id, user_id).await? {
+        return Ok(Some("You can only add collections inside ones you manage".to_string()));
+    }
+    Ok(None)
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/collections", get(list_collections))
@@ -110,56 +539,95 @@ pub fn router() -> Router<AppState> {
         .route("/collections/{id}/edit", get(edit_collection_form))
         .route("/collections/{id}", post(update_collection))
         .route("/collections/{id}", delete(delete_collection))
-        .route("/collections/{id}/regenerate-invite", post(regenerate_invite))
+        .route("/collections/{id}/invites", post(create_invite))
+        .route("/collections/{id}/invites/{invite_id}", delete(revoke_invite))
         .route("/collections/{id}/leave", post(leave_collection))
+        .route("/collections/{id}/transfer", post(transfer_ownership))
+        .route("/collections/{id}/reschedule", post(reschedule_entries))
+        .route("/collections/{id}/activity", get(collection_activity))
+        .route("/collections/{id}/progress", get(collection_progress))
+        .route("/collections/{id}/share", post(share_collection))
+        .route("/collections/{id}/share", delete(unshare_collection))
         .route("/collections/{id}/members/{user_id}", delete(remove_member))
+        .route("/collections/{id}/members/{user_id}/role", post(set_member_role))
 }
 
 async fn list_collections(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
 ) -> Result<impl IntoResponse, AppError> {
+    render_collection_list(&state.db, user, None).await
+}
+
+async fn render_collection_list(
+    db: &sqlx::SqlitePool,
+    user: User,
+    join_error: Option<String>,
+) -> Result<Html<String>, AppError> {
     let collections: Vec<CollectionWithCount> = sqlx::query_as(
         r#"
-        SELECT c.*, COUNT(DISTINCT cm.user_id) + 1 as member_count
+        SELECT c.*,
+            (SELECT COUNT(*) FROM collection_members cm WHERE cm.collection_id = c.id) + 1 as member_count,
+            (SELECT role FROM collection_access a WHERE a.collection_id = c.id AND a.user_id = ?) as role
         FROM collections c
-        LEFT JOIN collection_members cm ON cm.collection_id = c.id
-        WHERE c.owner_id = ? OR c.id IN (SELECT collection_id FROM collection_members WHERE user_id = ?)
-        GROUP BY c.id
+        WHERE c.id IN (SELECT collection_id FROM collection_access WHERE user_id = ?)
         ORDER BY c.name
         "#
     )
     .bind(&user.id)
     .bind(&user.id)
-    .fetch_all(&state.db)
+    .fetch_all(db)
     .await
     .unwrap_or_default();
 
-    let views: Vec<CollectionView> = collections
+    let collections = collections.into_iter().map(|cwc| cwc.into_collection_and_count()).collect();
+    let views: Vec<CollectionView> = nest_collections(collections, |(c, _, _)| c)
         .into_iter()
-        .map(|cwc| {
-            let (c, count) = cwc.into_collection_and_count();
-            CollectionView {
-                is_owner: c.owner_id == user.id,
-                id: c.id,
-                name: c.name,
-                member_count: count,
-            }
+        .map(|(depth, (c, count, role))| CollectionView {
+            is_owner: c.owner_id == user.id,
+            role,
+            depth: depth.min(MAX_DEPTH),
+            id: c.id,
+            name: c.name,
+            member_count: count,
         })
         .collect();
 
     let template = CollectionListTemplate {
         collections: views,
+        join_error,
         static_hash: crate::STATIC_HASH,
         user: Some(user),
     };
     Ok(Html(template.render()?))
 }
 
-async fn new_collection_form(AuthUser(user): AuthUser) -> Result<impl IntoResponse, AppError> {
+async fn new_collection_form(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<NewCollectionQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    render_collection_form(&state.db, user, None, query.parent_id, HashMap::new()).await
+}
+
+async fn render_collection_form(
+    db: &sqlx::SqlitePool,
+    user: User,
+    collection: Option<Collection>,
+    parent_id: Option<String>,
+    errors: HashMap<String, String>,
+) -> Result<Html<String>, AppError> {
+    let mut parents = fetch_collection_options(db, &user.id, CollectionRole::Admin).await?;
+    if let Some(collection) = &collection {
+        let subtree = fetch_collection_subtree(db, &collection.id).await?;
+        parents.retain(|p| !subtree.contains(&p.id));
+    }
+
     let template = CollectionFormTemplate {
-        collection: None,
-        errors: HashMap::new(),
+        collection,
+        parents,
+        parent_id: parent_id.unwrap_or_default(),
+        errors,
         static_hash: crate::STATIC_HASH,
         user: Some(user),
     };
@@ -171,21 +639,34 @@ async fn create_collection(
     AuthUser(user): AuthUser,
     Form(form): Form<CollectionForm>,
 ) -> Result<impl IntoResponse, AppError> {
-    let errors = validate_collection_form(&form);
+    let mut errors = validate_collection_form(&form);
+    if let Some(parent_id) = form.parent_id()
+        && let Some(error) = validate_parent(&state.db, &user.id, None, parent_id).await?
+    {
+        errors.insert("parent_id".to_string(), error);
+    }
     if !errors.is_empty() {
-        let template = CollectionFormTemplate {
-            collection: None,
-            errors,
-            static_hash: crate::STATIC_HASH,
-            user: Some(user),
-        };
-        return Ok(Html(template.render()?).into_response());
+        let parent_id = form.parent_id;
+        return Ok(render_collection_form(&state.db, user, None, parent_id, errors).await?.into_response());
     }
 
-    let collection = Collection::new(user.id, form.name);
+    let mut collection = Collection::new(user.id, form.name.clone());
+    collection.parent_id = form.parent_id().map(String::from);
+    collection.default_duration = form.schedule().map(|(duration, _)| duration);
+    collection.default_interval = form.schedule().map(|(_, interval)| interval);
+    collection.default_tags = form.tags();
+    insert_collection(&mut *state.db.acquire().await?, &collection).await?;
+
+    Ok(Redirect::to("/collections").into_response())
+}
 
+/// Insert a new collection along with its first invite
+pub async fn insert_collection(conn: &mut sqlx::SqliteConnection, collection: &Collection) -> Result<(), sqlx::Error> {
     sqlx::query(
-        "INSERT INTO collections (id, owner_id, name, invite_code, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)"
+        r#"
+        INSERT INTO collections (id, owner_id, name, invite_code, created_at, updated_at, default_duration, default_interval, default_tags, parent_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
     )
     .bind(&collection.id)
     .bind(&collection.owner_id)
@@ -193,10 +674,44 @@ async fn create_collection(
     .bind(&collection.invite_code)
     .bind(&collection.created_at)
     .bind(&collection.updated_at)
-    .execute(&state.db)
+    .bind(collection.default_duration)
+    .bind(collection.default_interval)
+    .bind(&collection.default_tags)
+    .bind(&collection.parent_id)
+    .execute(&mut *conn)
     .await?;
 
-    Ok(Redirect::to("/collections").into_response())
+    // Start with one open invite so the collection can be shared right away
+    let invite = CollectionInvite::new(
+        collection.id.clone(),
+        collection.invite_code.clone(),
+        None,
+        CollectionRole::default(),
+        None,
+        None,
+    );
+    insert_invite(&mut *conn, &invite).await
+}
+
+async fn insert_invite(db: impl sqlx::SqliteExecutor<'_>, invite: &CollectionInvite) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO collection_invites (id, collection_id, code, label, role, max_uses, use_count, expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&invite.id)
+    .bind(&invite.collection_id)
+    .bind(&invite.code)
+    .bind(&invite.label)
+    .bind(invite.role)
+    .bind(invite.max_uses)
+    .bind(invite.use_count)
+    .bind(&invite.expires_at)
+    .bind(&invite.created_at)
+    .execute(db)
+    .await?;
+    Ok(())
 }
 
 async fn join_collection(
@@ -204,29 +719,70 @@ async fn join_collection(
     AuthUser(user): AuthUser,
     Form(form): Form<JoinForm>,
 ) -> Result<impl IntoResponse, AppError> {
-    let collection: Option<Collection> = sqlx::query_as(
-        "SELECT * FROM collections WHERE invite_code = ?"
+    let invite: Option<CollectionInvite> = sqlx::query_as(
+        "SELECT * FROM collection_invites WHERE code = ?"
     )
-    .bind(&form.invite_code)
+    .bind(form.invite_code.trim())
     .fetch_optional(&state.db)
     .await?;
 
-    if let Some(collection) = collection {
-        if collection.owner_id == user.id {
-            return Ok(Redirect::to("/collections"));
-        }
-        let member = CollectionMember::new(collection.id, user.id);
-        sqlx::query(
-            "INSERT OR IGNORE INTO collection_members (collection_id, user_id, joined_at) VALUES (?, ?, ?)"
-        )
-        .bind(&member.collection_id)
-        .bind(&member.user_id)
-        .bind(&member.joined_at)
-        .execute(&state.db)
-        .await?;
+    let Some(invite) = invite else {
+        let error = Some("That invite code isn't valid".to_string());
+        return Ok(render_collection_list(&state.db, user, error).await?.into_response());
+    };
+
+    // Owners and existing members keep their role and don't use up the invite
+    if CollectionRole::load(&state.db, &invite.collection_id, &user.id).await?.is_some() {
+        return Ok(Redirect::to("/collections").into_response());
     }
 
-    Ok(Redirect::to("/collections"))
+    let now = Utc::now();
+    if let Some(reason) = invite.unusable_reason(now) {
+        return Ok(render_collection_list(&state.db, user, Some(reason.to_string())).await?.into_response());
+    }
+
+    // Add the member and claim a use together: a use only counts when someone
+    // actually joined
+    let mut tx = state.db.begin().await?;
+
+    let member = CollectionMember::new(invite.collection_id, user.id.clone(), invite.role);
+    let joined = sqlx::query(
+        "INSERT OR IGNORE INTO collection_members (collection_id, user_id, joined_at, role) VALUES (?, ?, ?, ?)"
+    )
+    .bind(&member.collection_id)
+    .bind(&member.user_id)
+    .bind(&member.joined_at)
+    .bind(member.role)
+    .execute(&mut *tx)
+    .await?;
+    if joined.rows_affected() == 0 {
+        return Ok(Redirect::to("/collections").into_response());
+    }
+
+    // Re-check the limits in the update so concurrent joins can't overshoot them
+    let claimed = sqlx::query(
+        r#"
+        UPDATE collection_invites SET use_count = use_count + 1
+        WHERE id = ? AND (max_uses IS NULL OR use_count < max_uses)
+        AND (expires_at IS NULL OR expires_at > ?)
+        "#
+    )
+    .bind(&invite.id)
+    .bind(now.to_rfc3339())
+    .execute(&mut *tx)
+    .await?;
+    if claimed.rows_affected() == 0 {
+        tx.rollback().await?;
+        let error = Some("This invite has no uses left".to_string());
+        return Ok(render_collection_list(&state.db, user, error).await?.into_response());
+    }
+
+    CollectionEvent::new(member.collection_id, member.user_id, EventKind::MemberJoined)
+        .record(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Redirect::to("/collections").into_response())
 }
 
 async fn show_collection(
@@ -234,41 +790,115 @@ async fn show_collection(
     AuthUser(user): AuthUser,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
+    render_collection_page(&state.db, user, &id, HashMap::new()).await
+}
+
+async fn render_collection_page(
+    db: &sqlx::SqlitePool,
+    user: User,
+    id: &str,
+    errors: HashMap<String, String>,
+) -> Result<Response, AppError> {
     // Verify user has access (owner or member)
-    let collection: Option<Collection> = sqlx::query_as(
+    let Some(role) = CollectionRole::load(db, id, &user.id).await? else {
+        return Ok(Redirect::to("/collections").into_response());
+    };
+
+    let collection: Collection = sqlx::query_as("SELECT * FROM collections WHERE id = ?")
+        .bind(id)
+        .fetch_one(db)
+        .await?;
+
+    let (owner_name,): (String,) = sqlx::query_as("SELECT name FROM users WHERE id = ?")
+        .bind(&collection.owner_id)
+        .fetch_one(db)
+        .await?;
+
+    let ancestors: Vec<Collection> = sqlx::query_as(
         r#"
+        WITH RECURSIVE ancestors(id, depth) AS (
+            SELECT parent_id, 1 FROM collections WHERE id = ? AND parent_id IS NOT NULL
+            UNION ALL
+            SELECT c.parent_id, a.depth + 1 FROM collections c
+            JOIN ancestors a ON a.id = c.id
+            WHERE c.parent_id IS NOT NULL
+        )
         SELECT c.* FROM collections c
-        WHERE c.id = ? AND (c.owner_id = ? OR c.id IN (
-            SELECT collection_id FROM collection_members WHERE user_id = ?
-        ))
+        JOIN ancestors a ON a.id = c.id
+        WHERE c.id IN (SELECT collection_id FROM collection_access WHERE user_id = ?)
+        ORDER BY a.depth DESC
         "#
     )
-    .bind(&id)
-    .bind(&user.id)
+    .bind(id)
     .bind(&user.id)
-    .fetch_optional(&state.db)
+    .fetch_all(db)
     .await?;
 
-    let Some(collection) = collection else {
-        return Ok(Redirect::to("/collections").into_response());
-    };
+    let subcollections: Vec<Collection> =
+        sqlx::query_as("SELECT * FROM collections WHERE parent_id = ? ORDER BY name")
+            .bind(id)
+            .fetch_all(db)
+            .await?;
 
-    let members: Vec<User> = sqlx::query_as(
+    let members: Vec<MemberWithRole> = sqlx::query_as(
         r#"
-        SELECT u.* FROM users u
+        SELECT u.*, cm.role FROM users u
         JOIN collection_members cm ON cm.user_id = u.id
         WHERE cm.collection_id = ?
         "#
     )
-    .bind(&id)
-    .fetch_all(&state.db)
+    .bind(id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    let rows: Vec<EntryWithCount> = sqlx::query_as(
+        r#"
+        SELECT e.*, COUNT(v.id) as visit_count
+        FROM entries e
+        LEFT JOIN visits v ON v.entry_id = e.id
+        WHERE e.collection_id = ?
+        GROUP BY e.id
+        ORDER BY e.pinned DESC, e.dismissed_at DESC NULLS FIRST
+        "#
+    )
+    .bind(id)
+    .fetch_all(db)
     .await
     .unwrap_or_default();
 
+    let now = Utc::now();
+    let mut entries: Vec<EntryView> = rows
+        .into_iter()
+        .map(|r| {
+            let (entry, count) = r.into_entry_and_count();
+            build_entry_view(entry, count, now)
+        })
+        .collect();
+    fill_comment_counts(db, &user.id, &mut entries).await;
+
+    let invites: Vec<CollectionInvite> = if role.can_manage_members() {
+        sqlx::query_as("SELECT * FROM collection_invites WHERE collection_id = ? ORDER BY created_at")
+            .bind(id)
+            .fetch_all(db)
+            .await?
+    } else {
+        vec![]
+    };
+    let invites = invites.into_iter().map(|invite| InviteView::new(invite, now)).collect();
+
     let template = CollectionShowTemplate {
         is_owner: collection.owner_id == user.id,
+        role,
+        roles: CollectionRole::ASSIGNABLE.into_iter().filter(|r| role.outranks(*r)).collect(),
         collection,
+        ancestors,
+        subcollections,
+        owner_name,
         members,
+        entries,
+        invites,
+        errors,
         static_hash: crate::STATIC_HASH,
         user: Some(user),
     };
@@ -292,13 +922,8 @@ async fn edit_collection_form(
         return Ok(Redirect::to("/collections").into_response());
     };
 
-    let template = CollectionFormTemplate {
-        collection: Some(collection),
-        errors: HashMap::new(),
-        static_hash: crate::STATIC_HASH,
-        user: Some(user),
-    };
-    Ok(Html(template.render()?).into_response())
+    let parent_id = collection.parent_id.clone();
+    Ok(render_collection_form(&state.db, user, Some(collection), parent_id, HashMap::new()).await?.into_response())
 }
 
 async fn update_collection(
@@ -307,34 +932,50 @@ async fn update_collection(
     Path(id): Path<String>,
     Form(form): Form<CollectionForm>,
 ) -> Result<impl IntoResponse, AppError> {
-    let errors = validate_collection_form(&form);
-    if !errors.is_empty() {
-        let collection: Option<Collection> = sqlx::query_as(
-            "SELECT * FROM collections WHERE id = ? AND owner_id = ?"
-        )
-        .bind(&id)
-        .bind(&user.id)
-        .fetch_optional(&state.db)
-        .await?;
+    // Only the owner edits the collection, so check that before saying
+    // anything about the form
+    let collection: Option<Collection> = sqlx::query_as(
+        "SELECT * FROM collections WHERE id = ? AND owner_id = ?"
+    )
+    .bind(&id)
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await?;
 
-        let template = CollectionFormTemplate {
-            collection,
-            errors,
-            static_hash: crate::STATIC_HASH,
-            user: Some(user),
-        };
-        return Ok(Html(template.render()?).into_response());
+    let Some(collection) = collection else {
+        return Ok(Redirect::to("/collections").into_response());
+    };
+
+    let mut errors = validate_collection_form(&form);
+    if let Some(parent_id) = form.parent_id()
+        && let Some(error) = validate_parent(&state.db, &user.id, Some(&id), parent_id).await?
+    {
+        errors.insert("parent_id".to_string(), error);
+    }
+    if !errors.is_empty() {
+        let parent_id = form.parent_id;
+        return Ok(render_collection_form(&state.db, user, Some(collection), parent_id, errors).await?.into_response());
     }
 
     let now = chrono::Utc::now().to_rfc3339();
 
-    sqlx::query("UPDATE collections SET name = ?, updated_at = ? WHERE id = ? AND owner_id = ?")
-        .bind(&form.name)
-        .bind(&now)
-        .bind(&id)
-        .bind(&user.id)
-        .execute(&state.db)
-        .await?;
+    let schedule = form.schedule();
+    sqlx::query(
+        r#"
+        UPDATE collections SET name = ?, default_duration = ?, default_interval = ?, default_tags = ?, parent_id = ?, updated_at = ?
+        WHERE id = ? AND owner_id = ?
+        "#
+    )
+    .bind(&form.name)
+    .bind(schedule.map(|(duration, _)| duration))
+    .bind(schedule.map(|(_, interval)| interval))
+    .bind(form.tags())
+    .bind(form.parent_id())
+    .bind(&now)
+    .bind(&id)
+    .bind(&user.id)
+    .execute(&state.db)
+    .await?;
 
     Ok(Redirect::to("/collections").into_response())
 }
@@ -353,22 +994,324 @@ async fn delete_collection(
     Ok(([("HX-Redirect", "/collections")], ""))
 }
 
-async fn regenerate_invite(
+/// Whether the user is the collection's owner or one of its admins
+async fn can_manage_members(db: &sqlx::SqlitePool, collection_id: &str, user_id: &str) -> Result<bool, sqlx::Error> {
+    let role = CollectionRole::load(db, collection_id, user_id).await?;
+    Ok(role.is_some_and(|r| r.can_manage_members()))
+}
+
+/// Whether the user may change or remove a member: their role must outrank
+/// the member's, so only the owner can manage admins
+async fn can_manage_member(
+    db: &sqlx::SqlitePool,
+    collection_id: &str,
+    user_id: &str,
+    member_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let Some(role) = CollectionRole::load(db, collection_id, user_id).await? else {
+        return Ok(false);
+    };
+    let member_role: Option<(CollectionRole,)> =
+        sqlx::query_as("SELECT role FROM collection_members WHERE collection_id = ? AND user_id = ?")
+            .bind(collection_id)
+            .bind(member_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(member_role.is_some_and(|(member_role,)| role.outranks(member_role)))
+}
+
+/// Create a public link for the collection, replacing any existing one
+async fn share_collection(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let new_code = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
+    if can_manage_members(&state.db, &id, &user.id).await? {
+        sqlx::query("UPDATE collections SET share_token = ?, updated_at = ? WHERE id = ?")
+            .bind(uuid::Uuid::new_v4().simple().to_string())
+            .bind(Utc::now().to_rfc3339())
+            .bind(&id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(Redirect::to(&format!("/collections/{}", id)))
+}
+
+async fn unshare_collection(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if can_manage_members(&state.db, &id, &user.id).await? {
+        sqlx::query("UPDATE collections SET share_token = NULL, updated_at = ? WHERE id = ?")
+            .bind(Utc::now().to_rfc3339())
+            .bind(&id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(([("HX-Redirect", format!("/collections/{}", id))], "").into_response())
+}
 
-    sqlx::query("UPDATE collections SET invite_code = ?, updated_at = ? WHERE id = ? AND owner_id = ?")
-        .bind(&new_code)
+async fn create_invite(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<InviteForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(role) = CollectionRole::load(&state.db, &id, &user.id).await?.filter(|r| r.can_manage_members()) else {
+        return Ok(Redirect::to(&format!("/collections/{}", id)).into_response());
+    };
+
+    let mut errors = validate_invite_form(&form);
+    if !role.outranks(form.role) {
+        errors.insert("role".to_string(), "Only the owner can invite admins".to_string());
+    }
+    if !errors.is_empty() {
+        return render_collection_page(&state.db, user, &id, errors).await;
+    }
+
+    let label = form.label.trim();
+    let expires_at = parse_optional_count(&form.expires_in_days)
+        .ok()
+        .flatten()
+        .map(|days| (Utc::now() + Duration::days(days)).to_rfc3339());
+    let invite = CollectionInvite::new(
+        id.clone(),
+        uuid::Uuid::new_v4().to_string(),
+        (!label.is_empty()).then(|| label.to_string()),
+        form.role,
+        parse_optional_count(&form.max_uses).ok().flatten(),
+        expires_at,
+    );
+    insert_invite(&state.db, &invite).await?;
+
+    Ok(Redirect::to(&format!("/collections/{}", id)).into_response())
+}
+
+async fn revoke_invite(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((collection_id, invite_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    if can_manage_members(&state.db, &collection_id, &user.id).await? {
+        sqlx::query("DELETE FROM collection_invites WHERE id = ? AND collection_id = ?")
+            .bind(&invite_id)
+            .bind(&collection_id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(([("HX-Redirect", format!("/collections/{}", collection_id))], "").into_response())
+}
+
+/// Matrix of entries against members showing who has read what
+async fn collection_progress(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if CollectionRole::load(&state.db, &id, &user.id).await?.is_none() {
+        return Ok(Redirect::to("/collections").into_response());
+    }
+
+    let collection: Collection = sqlx::query_as("SELECT * FROM collections WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&state.db)
+        .await?;
+
+    // The owner first, then members by name, including those of parent collections
+    let members: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT u.id, u.name FROM users u
+        JOIN collection_access a ON a.user_id = u.id
+        WHERE a.collection_id = ?
+        ORDER BY a.role != 'owner', u.name
+        "#
+    )
+    .bind(&id)
+    .fetch_all(&state.db)
+    .await?;
+    let mut members: Vec<ProgressMember> = members
+        .into_iter()
+        .map(|(id, name)| ProgressMember { id, name, read: 0, due: 0 })
+        .collect();
+
+    let entries: Vec<Entry> = sqlx::query_as(
+        "SELECT * FROM entries WHERE collection_id = ? ORDER BY pinned DESC, title"
+    )
+    .bind(&id)
+    .fetch_all(&state.db)
+    .await?;
+
+    let visits: Vec<(String, String, String)> = sqlx::query_as(
+        r#"
+        SELECT v.entry_id, v.user_id, MAX(v.visited_at)
+        FROM visits v
+        JOIN entries e ON e.id = v.entry_id
+        WHERE e.collection_id = ?
+        GROUP BY v.entry_id, v.user_id
+        "#
+    )
+    .bind(&id)
+    .fetch_all(&state.db)
+    .await?;
+    let last_visits: HashMap<(String, String), String> = visits
+        .into_iter()
+        .map(|(entry_id, user_id, visited_at)| ((entry_id, user_id), visited_at))
+        .collect();
+
+    let rows = build_progress(entries, &mut members, &last_visits, Utc::now());
+
+    let template = ProgressTemplate {
+        collection,
+        members,
+        rows,
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// A page of the collection's activity, newest first. Loaded into the show
+/// page by htmx, which also polls it and appends older pages.
+async fn collection_activity(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<ActivityQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    if CollectionRole::load(&state.db, &id, &user.id).await?.is_none() {
+        return Ok(Redirect::to("/collections").into_response());
+    }
+
+    let mut rows: Vec<EventWithUser> = sqlx::query_as(
+        r#"
+        SELECT e.rowid AS seq, e.*, u.name AS user_name
+        FROM collection_events e
+        LEFT JOIN users u ON u.id = e.user_id
+        WHERE e.collection_id = ?
+        AND (? = '' OR e.user_id = ?)
+        AND (? IS NULL OR e.rowid < ?)
+        ORDER BY e.rowid DESC
+        LIMIT ?
+        "#
+    )
+    .bind(&id)
+    .bind(&query.member)
+    .bind(&query.member)
+    .bind(query.before)
+    .bind(query.before)
+    .bind(ACTIVITY_PAGE_SIZE + 1)
+    .fetch_all(&state.db)
+    .await?;
+
+    let has_more = rows.len() as i64 > ACTIVITY_PAGE_SIZE;
+    rows.truncate(ACTIVITY_PAGE_SIZE as usize);
+    let next_before = if has_more { rows.last().map(|r| r.seq) } else { None };
+
+    let now = Utc::now();
+    let events = rows
+        .into_iter()
+        .map(|r| EventView {
+            user_name: r.user_name.unwrap_or_else(|| "Someone".to_string()),
+            verb: r.event.kind.verb(),
+            entry_id: r.event.entry_id,
+            subject: r.event.subject,
+            when: format_last_viewed(&Some(r.event.created_at), now).unwrap_or_default(),
+        })
+        .collect();
+
+    let template = ActivityTemplate {
+        collection_id: id,
+        events,
+        member: query.member,
+        first_page: query.before.is_none(),
+        next_before,
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+/// Apply one schedule to every entry in the collection
+async fn reschedule_entries(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<RescheduleForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = CollectionRole::load(&state.db, &id, &user.id).await?;
+    if !role.is_some_and(|r| r.can_edit_entries()) {
+        return Ok(Redirect::to(&format!("/collections/{}", id)).into_response());
+    }
+
+    let Ok(Some(duration)) = parse_optional_count(&form.duration) else {
+        let mut errors = HashMap::new();
+        errors.insert("reschedule".to_string(), "Duration must be at least 1".to_string());
+        return render_collection_page(&state.db, user, &id, errors).await;
+    };
+
+    reschedule_collection_entries(&state.db, &id, &user.id, duration, form.interval).await?;
+
+    Ok(Redirect::to(&format!("/collections/{}", id)).into_response())
+}
+
+/// Make a member the owner. The previous owner stays on as an admin, so they
+/// can leave afterwards like any other member.
+async fn transfer_ownership(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<TransferForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = CollectionRole::load(&state.db, &id, &user.id).await?;
+    // Only a direct member, like the one picked when an owner is deleted;
+    // access inherited from a parent collection doesn't count
+    let is_member: Option<(String,)> =
+        sqlx::query_as("SELECT user_id FROM collection_members WHERE collection_id = ? AND user_id = ?")
+            .bind(&id)
+            .bind(&form.user_id)
+            .fetch_optional(&state.db)
+            .await?;
+    if role != Some(CollectionRole::Owner) || is_member.is_none() {
+        return Ok(Redirect::to(&format!("/collections/{}", id)));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let mut tx = state.db.begin().await?;
+
+    sqlx::query("UPDATE collections SET owner_id = ?, updated_at = ? WHERE id = ?")
+        .bind(&form.user_id)
         .bind(&now)
         .bind(&id)
-        .bind(&user.id)
-        .execute(&state.db)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM collection_members WHERE collection_id = ? AND user_id = ?")
+        .bind(&id)
+        .bind(&form.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let member = CollectionMember::new(id.clone(), user.id, CollectionRole::Admin);
+    sqlx::query("INSERT INTO collection_members (collection_id, user_id, joined_at, role) VALUES (?, ?, ?, ?)")
+        .bind(&member.collection_id)
+        .bind(&member.user_id)
+        .bind(&member.joined_at)
+        .bind(member.role)
+        .execute(&mut *tx)
         .await?;
 
+    let name: Option<(String,)> = sqlx::query_as("SELECT name FROM users WHERE id = ?")
+        .bind(&form.user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let mut event = CollectionEvent::new(id.clone(), member.user_id, EventKind::OwnershipTransferred);
+    event.subject = name.map(|(name,)| name);
+    event.record(&mut *tx).await?;
+
+    tx.commit().await?;
+
     Ok(Redirect::to(&format!("/collections/{}", id)))
 }
 
@@ -378,12 +1321,16 @@ async fn leave_collection(
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
     // Only members can leave (not owners)
-    sqlx::query("DELETE FROM collection_members WHERE collection_id = ? AND user_id = ?")
+    let left = sqlx::query("DELETE FROM collection_members WHERE collection_id = ? AND user_id = ?")
         .bind(&id)
         .bind(&user.id)
         .execute(&state.db)
         .await?;
 
+    if left.rows_affected() > 0 {
+        CollectionEvent::new(id, user.id, EventKind::MemberLeft).record(&state.db).await?;
+    }
+
     Ok(Redirect::to("/collections"))
 }
 
@@ -392,26 +1339,47 @@ async fn remove_member(
     AuthUser(user): AuthUser,
     Path((collection_id, member_id)): Path<(String, String)>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Verify user is owner
-    let collection: Option<Collection> = sqlx::query_as(
-        "SELECT * FROM collections WHERE id = ? AND owner_id = ?"
-    )
-    .bind(&collection_id)
-    .bind(&user.id)
-    .fetch_optional(&state.db)
-    .await?;
-
-    if collection.is_some() {
-        sqlx::query("DELETE FROM collection_members WHERE collection_id = ? AND user_id = ?")
+    if can_manage_member(&state.db, &collection_id, &user.id, &member_id).await? {
+        let removed = sqlx::query("DELETE FROM collection_members WHERE collection_id = ? AND user_id = ?")
             .bind(&collection_id)
             .bind(&member_id)
             .execute(&state.db)
             .await?;
+
+        if removed.rows_affected() > 0 {
+            let name: Option<(String,)> = sqlx::query_as("SELECT name FROM users WHERE id = ?")
+                .bind(&member_id)
+                .fetch_optional(&state.db)
+                .await?;
+            let mut event = CollectionEvent::new(collection_id.clone(), user.id, EventKind::MemberRemoved);
+            event.subject = name.map(|(name,)| name);
+            event.record(&state.db).await?;
+        }
     }
 
     Ok(([("HX-Redirect", format!("/collections/{}", collection_id))], "").into_response())
 }
 
+async fn set_member_role(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((collection_id, member_id)): Path<(String, String)>,
+    Form(form): Form<RoleForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let role = CollectionRole::load(&state.db, &collection_id, &user.id).await?;
+    let assignable = CollectionRole::ASSIGNABLE.contains(&form.role) && role.is_some_and(|r| r.outranks(form.role));
+    if assignable && can_manage_member(&state.db, &collection_id, &user.id, &member_id).await? {
+        sqlx::query("UPDATE collection_members SET role = ? WHERE collection_id = ? AND user_id = ?")
+            .bind(form.role)
+            .bind(&collection_id)
+            .bind(&member_id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(Redirect::to(&format!("/collections/{}", collection_id)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,6 +1388,7 @@ mod tests {
     fn collection_form_valid() {
         let form = CollectionForm {
             name: "My Collection".to_string(),
+            ..Default::default()
         };
         assert!(validate_collection_form(&form).is_empty());
     }
@@ -428,17 +1397,173 @@ mod tests {
     fn collection_form_empty_name() {
         let form = CollectionForm {
             name: "   ".to_string(),
+            ..Default::default()
         };
         let errors = validate_collection_form(&form);
         assert!(errors.contains_key("name"));
     }
 
+    #[test]
+    fn collection_form_default_schedule() {
+        let form = CollectionForm {
+            name: "Reading".to_string(),
+            default_duration: "2".to_string(),
+            default_interval: Some(Interval::Weeks),
+            default_tags: "Rust, books,rust".to_string(),
+            parent_id: Some(String::new()),
+        };
+        assert!(validate_collection_form(&form).is_empty());
+        assert_eq!(form.schedule(), Some((2, Interval::Weeks)));
+        assert_eq!(form.tags(), "rust, books");
+        assert_eq!(form.parent_id(), None);
+
+        let form = CollectionForm {
+            name: "Reading".to_string(),
+            default_duration: "0".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_collection_form(&form).contains_key("default_duration"));
+        let form = CollectionForm {
+            name: "Reading".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(form.schedule(), None);
+    }
+
     #[test]
     fn collection_form_name_too_long() {
         let form = CollectionForm {
             name: "a".repeat(101),
+            ..Default::default()
         };
         let errors = validate_collection_form(&form);
         assert!(errors.contains_key("name"));
     }
+
+    fn invite_form(max_uses: &str, expires_in_days: &str) -> InviteForm {
+        InviteForm {
+            label: "Friends".to_string(),
+            role: CollectionRole::Viewer,
+            max_uses: max_uses.to_string(),
+            expires_in_days: expires_in_days.to_string(),
+        }
+    }
+
+    #[test]
+    fn invite_form_limits_are_optional() {
+        assert!(validate_invite_form(&invite_form("", "")).is_empty());
+        assert!(validate_invite_form(&invite_form("5", " 30 ")).is_empty());
+    }
+
+    #[test]
+    fn invite_form_rejects_bad_limits() {
+        let errors = validate_invite_form(&invite_form("0", "-1"));
+        assert!(errors.contains_key("max_uses"));
+        assert!(errors.contains_key("expires_in_days"));
+
+        let mut form = invite_form("", "");
+        form.role = CollectionRole::Owner;
+        assert!(validate_invite_form(&form).contains_key("role"));
+    }
+
+    #[test]
+    fn expiry_labels() {
+        let now: DateTime<Utc> = "2024-06-01T12:00:00+00:00".parse().unwrap();
+        assert_eq!(format_expiry("2024-06-04T13:00:00+00:00", now), "expires in 3 days");
+        assert_eq!(format_expiry("2024-06-01T17:30:00+00:00", now), "expires in 5 hours");
+        assert_eq!(format_expiry("2024-06-01T12:10:00+00:00", now), "expires in 1 hour");
+        assert_eq!(format_expiry("2024-05-01T12:00:00+00:00", now), "expired");
+    }
+
+    fn entry(id: &str, duration: i64) -> Entry {
+        Entry {
+            id: id.to_string(),
+            user_id: "u".to_string(),
+            collection_id: Some("c".to_string()),
+            url: "https://example.com".to_string(),
+            title: id.to_string(),
+            description: None,
+            duration,
+            interval: Interval::Days,
+            dismissed_at: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            priority: Default::default(),
+            pinned: false,
+        }
+    }
+
+    fn member(id: &str) -> ProgressMember {
+        ProgressMember {
+            id: id.to_string(),
+            name: id.to_string(),
+            read: 0,
+            due: 0,
+        }
+    }
+
+    #[test]
+    fn progress_is_per_member() {
+        let now: DateTime<Utc> = "2024-06-10T12:00:00+00:00".parse().unwrap();
+        let mut members = vec![member("alice"), member("bob")];
+        let mut visits = HashMap::new();
+        // Alice read "weekly" two days ago, so it's not due for her yet
+        visits.insert(("weekly".to_string(), "alice".to_string()), "2024-06-08T12:00:00+00:00".to_string());
+        // Bob read both, but "daily" a while back
+        visits.insert(("weekly".to_string(), "bob".to_string()), "2024-06-09T12:00:00+00:00".to_string());
+        visits.insert(("daily".to_string(), "bob".to_string()), "2024-06-01T12:00:00+00:00".to_string());
+
+        let rows = build_progress(vec![entry("weekly", 7), entry("daily", 1)], &mut members, &visits, now);
+
+        assert_eq!(rows.len(), 2);
+        assert!(!rows[0].cells[0].due);
+        assert_eq!(rows[0].cells[0].last_visit.as_deref(), Some("2 days ago"));
+        assert!(rows[1].cells[0].due);
+        assert!(rows[1].cells[0].last_visit.is_none());
+        assert!(rows[1].cells[1].due);
+
+        assert_eq!((members[0].read, members[0].due), (1, 1));
+        assert_eq!((members[1].read, members[1].due), (2, 1));
+    }
+
+    fn collection(id: &str, parent_id: Option<&str>) -> Collection {
+        let mut collection = Collection::new("u1".to_string(), id.to_string());
+        collection.id = id.to_string();
+        collection.parent_id = parent_id.map(String::from);
+        collection
+    }
+
+    fn tree(collections: Vec<Collection>) -> Vec<(usize, String)> {
+        nest_collections(collections, |c| c)
+            .into_iter()
+            .map(|(depth, c)| (depth, c.id))
+            .collect()
+    }
+
+    #[test]
+    fn subcollections_follow_their_parent() {
+        let collections = vec![
+            collection("books", None),
+            collection("design", Some("team")),
+            collection("research", Some("design")),
+            collection("team", None),
+            collection("tools", Some("team")),
+        ];
+        assert_eq!(
+            tree(collections),
+            vec![
+                (0, "books".to_string()),
+                (0, "team".to_string()),
+                (1, "design".to_string()),
+                (2, "research".to_string()),
+                (1, "tools".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn subcollections_of_hidden_parents_are_top_level() {
+        let collections = vec![collection("design", Some("team")), collection("home", None)];
+        assert_eq!(tree(collections), vec![(0, "design".to_string()), (0, "home".to_string())]);
+    }
 }
@@ -0,0 +1,360 @@
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Redirect},
+    routing::post,
+    Form, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+
+use crate::auth::AuthUser;
+use crate::error::AppError;
+use crate::markdown;
+use crate::models::{Comment, Entry};
+use crate::routes::entries::{fetch_accessible_entry, format_last_viewed, EntryView, MAX_NOTE_LEN};
+use crate::AppState;
+
+#[derive(Deserialize)]
+pub struct CommentForm {
+    #[serde(default)]
+    body: String,
+    /// Set when replying to another comment
+    parent_id: Option<String>,
+}
+
+impl CommentForm {
+    fn check_length(&self) -> Result<(), AppError> {
+        if self.body.len() > MAX_NOTE_LEN {
+            return Err(AppError::BadRequest("Comment must be under 5000 characters".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// A comment joined with its author's name
+#[derive(FromRow)]
+struct CommentWithUser {
+    #[sqlx(flatten)]
+    comment: Comment,
+    user_name: Option<String>,
+}
+
+pub struct CommentView {
+    pub id: String,
+    pub user_name: String,
+    pub posted: String,
+    pub edited: bool,
+    pub body: String,
+    pub body_html: String,
+    /// Reply depth, capped so deep threads don't run off the page
+    pub indent: usize,
+    pub is_own: bool,
+    pub is_deleted: bool,
+}
+
+/// Deeper replies are shown at this indent
+const MAX_INDENT: usize = 4;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/entries/{id}/comments", post(add_comment))
+        .route("/entries/{id}/comments/{comment_id}", post(update_comment).delete(delete_comment))
+}
+
+/// An entry the user can comment on: a collection entry they can see
+async fn fetch_commentable_entry(db: &sqlx::SqlitePool, id: &str, user_id: &str) -> Result<Option<Entry>, sqlx::Error> {
+    let entry = fetch_accessible_entry(db, id, user_id).await?;
+    Ok(entry.filter(|e| e.collection_id.is_some()))
+}
+
+/// The entry's comments in thread order: each comment is followed by its
+/// replies, oldest first
+pub async fn fetch_comment_views(
+    db: &sqlx::SqlitePool,
+    entry_id: &str,
+    user_id: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<CommentView>, sqlx::Error> {
+    let comments: Vec<CommentWithUser> = sqlx::query_as(
+        r#"
+        SELECT c.*, u.name as user_name
+        FROM entry_comments c
+        LEFT JOIN users u ON u.id = c.user_id
+        WHERE c.entry_id = ?
+        ORDER BY c.created_at, c.rowid
+        "#
+    )
+    .bind(entry_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(thread_comments(comments)
+        .into_iter()
+        .map(|(depth, c)| {
+            let is_deleted = c.comment.is_deleted();
+            CommentView {
+                posted: format_last_viewed(&Some(c.comment.created_at.clone()), now).unwrap_or_default(),
+                edited: c.comment.updated_at != c.comment.created_at,
+                body_html: if is_deleted { String::new() } else { markdown::render_markdown(&c.comment.body) },
+                user_name: c.user_name.unwrap_or_else(|| "Deleted user".to_string()),
+                indent: depth.min(MAX_INDENT),
+                is_own: !is_deleted && c.comment.user_id.as_deref() == Some(user_id),
+                is_deleted,
+                id: c.comment.id,
+                body: c.comment.body,
+            }
+        })
+        .collect())
+}
+
+/// Order comments depth-first, pairing each with its reply depth. Comments
+/// must arrive oldest first; a reply whose parent is missing becomes top-level.
+fn thread_comments(comments: Vec<CommentWithUser>) -> Vec<(usize, CommentWithUser)> {
+    let ids: HashSet<String> = comments.iter().map(|c| c.comment.id.clone()).collect();
+    let mut replies: HashMap<Option<String>, Vec<CommentWithUser>> = HashMap::new();
+    for c in comments {
+        let parent = c.comment.parent_id.clone().filter(|p| ids.contains(p));
+        replies.entry(parent).or_default().push(c);
+    }
+
+    let mut threaded = Vec::new();
+    let mut stack: Vec<(usize, CommentWithUser)> = replies
+        .remove(&None)
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .map(|c| (0, c))
+        .collect();
+    while let Some((depth, c)) = stack.pop() {
+        let children = replies.remove(&Some(c.comment.id.clone())).unwrap_or_default();
+        stack.extend(children.into_iter().rev().map(|child| (depth + 1, child)));
+        threaded.push((depth, c));
+    }
+    threaded
+}
+
+/// Set the comment count on each entry view, for the entry cards
+pub async fn fill_comment_counts(db: &sqlx::SqlitePool, user_id: &str, entries: &mut [EntryView]) {
+    let counts: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT c.entry_id, COUNT(*)
+        FROM entry_comments c
+        JOIN entries e ON e.id = c.entry_id
+        WHERE c.deleted_at IS NULL AND e.collection_id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ?
+        )
+        GROUP BY c.entry_id
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    let counts: HashMap<String, i64> = counts.into_iter().collect();
+    for entry in entries {
+        entry.comment_count = counts.get(&entry.id).copied().unwrap_or(0);
+    }
+}
+
+async fn add_comment(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<CommentForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(entry) = fetch_commentable_entry(&state.db, &id, &user.id).await? else {
+        return Ok(Redirect::to("/"));
+    };
+    form.check_length()?;
+
+    let body = form.body.trim();
+    if body.is_empty() {
+        return Ok(Redirect::to(&format!("/entries/{}", id)));
+    }
+
+    // Replies must be to a live comment on the same entry
+    let parent_id = form.parent_id.filter(|p| !p.is_empty());
+    if let Some(parent_id) = &parent_id {
+        let parent: Option<(String,)> =
+            sqlx::query_as("SELECT id FROM entry_comments WHERE id = ? AND entry_id = ? AND deleted_at IS NULL")
+                .bind(parent_id)
+                .bind(&entry.id)
+                .fetch_optional(&state.db)
+                .await?;
+        if parent.is_none() {
+            return Ok(Redirect::to(&format!("/entries/{}", id)));
+        }
+    }
+
+    let comment = Comment::new(entry.id, user.id, parent_id, body.to_string());
+    sqlx::query(
+        r#"
+        INSERT INTO entry_comments (id, entry_id, user_id, parent_id, body, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&comment.id)
+    .bind(&comment.entry_id)
+    .bind(&comment.user_id)
+    .bind(&comment.parent_id)
+    .bind(&comment.body)
+    .bind(&comment.created_at)
+    .bind(&comment.updated_at)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Redirect::to(&format!("/entries/{}#comment-{}", id, comment.id)))
+}
+
+async fn update_comment(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((id, comment_id)): Path<(String, String)>,
+    Form(form): Form<CommentForm>,
+) -> Result<impl IntoResponse, AppError> {
+    if fetch_commentable_entry(&state.db, &id, &user.id).await?.is_none() {
+        return Ok(Redirect::to("/"));
+    }
+    form.check_length()?;
+
+    let body = form.body.trim();
+    if !body.is_empty() {
+        // Only the author can edit their comment
+        sqlx::query(
+            r#"
+            UPDATE entry_comments SET body = ?, updated_at = ?
+            WHERE id = ? AND entry_id = ? AND user_id = ? AND deleted_at IS NULL
+            "#
+        )
+        .bind(body)
+        .bind(Utc::now().to_rfc3339())
+        .bind(&comment_id)
+        .bind(&id)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+    }
+
+    Ok(Redirect::to(&format!("/entries/{}#comment-{}", id, comment_id)))
+}
+
+async fn delete_comment(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((id, comment_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    if fetch_commentable_entry(&state.db, &id, &user.id).await?.is_none() {
+        return Ok(([("HX-Redirect", "/".to_string())], "").into_response());
+    }
+
+    let comment: Option<Comment> = sqlx::query_as(
+        "SELECT * FROM entry_comments WHERE id = ? AND entry_id = ? AND user_id = ? AND deleted_at IS NULL",
+    )
+    .bind(&comment_id)
+    .bind(&id)
+    .bind(&user.id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    if let Some(comment) = comment {
+        // Keep a placeholder while others have replied, so their replies stay threaded
+        let (replies,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_comments WHERE parent_id = ?")
+            .bind(&comment.id)
+            .fetch_one(&state.db)
+            .await?;
+        if replies > 0 {
+            sqlx::query("UPDATE entry_comments SET body = '', deleted_at = ? WHERE id = ?")
+                .bind(Utc::now().to_rfc3339())
+                .bind(&comment.id)
+                .execute(&state.db)
+                .await?;
+        } else {
+            sqlx::query("DELETE FROM entry_comments WHERE id = ?")
+                .bind(&comment.id)
+                .execute(&state.db)
+                .await?;
+            prune_deleted_ancestors(&state.db, comment.parent_id).await?;
+        }
+    }
+
+    Ok(([("HX-Redirect", format!("/entries/{}", id))], "").into_response())
+}
+
+/// Remove deleted placeholders that no longer have any replies, walking up
+/// the thread from `parent_id`
+async fn prune_deleted_ancestors(db: &sqlx::SqlitePool, mut parent_id: Option<String>) -> Result<(), sqlx::Error> {
+    while let Some(id) = parent_id {
+        let parent: Option<Comment> = sqlx::query_as(
+            r#"
+            SELECT * FROM entry_comments c
+            WHERE c.id = ? AND c.deleted_at IS NOT NULL
+            AND NOT EXISTS (SELECT 1 FROM entry_comments r WHERE r.parent_id = c.id)
+            "#
+        )
+        .bind(&id)
+        .fetch_optional(db)
+        .await?;
+        let Some(parent) = parent else {
+            break;
+        };
+        sqlx::query("DELETE FROM entry_comments WHERE id = ?")
+            .bind(&parent.id)
+            .execute(db)
+            .await?;
+        parent_id = parent.parent_id;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: &str, parent_id: Option<&str>) -> CommentWithUser {
+        let mut comment = Comment::new("e1".to_string(), "u1".to_string(), parent_id.map(String::from), id.to_string());
+        comment.id = id.to_string();
+        CommentWithUser {
+            comment,
+            user_name: Some("One".to_string()),
+        }
+    }
+
+    fn order(comments: Vec<CommentWithUser>) -> Vec<(usize, String)> {
+        thread_comments(comments)
+            .into_iter()
+            .map(|(depth, c)| (depth, c.comment.id))
+            .collect()
+    }
+
+    #[test]
+    fn replies_follow_their_parent() {
+        let comments = vec![
+            comment("a", None),
+            comment("b", None),
+            comment("a1", Some("a")),
+            comment("b1", Some("b")),
+            comment("a1x", Some("a1")),
+            comment("a2", Some("a")),
+        ];
+        assert_eq!(
+            order(comments),
+            vec![
+                (0, "a".to_string()),
+                (1, "a1".to_string()),
+                (2, "a1x".to_string()),
+                (1, "a2".to_string()),
+                (0, "b".to_string()),
+                (1, "b1".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn orphaned_replies_become_top_level() {
+        let comments = vec![comment("a", None), comment("b", Some("gone"))];
+        assert_eq!(order(comments), vec![(0, "a".to_string()), (0, "b".to_string())]);
+    }
+}
@@ -1,6 +1,6 @@
 use askama::Template;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, RawQuery, State},
     response::{Html, IntoResponse, Redirect},
     routing::{delete, get, post},
     Form, Router,
@@ -8,11 +8,18 @@ use axum::{
 use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use sqlx::FromRow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::auth::AuthUser;
 use crate::error::AppError;
-use crate::models::{Collection, Entry, Interval, User, Visit};
+use crate::markdown;
+use crate::models::{
+    Collection, CollectionEvent, CollectionRole, Entry, EntryRevision, EntrySnapshot, EventKind, Interval, Priority, Tag, TagNamespace,
+    User, UserSettings, Visit,
+};
+use crate::routes::comments::{fetch_comment_views, fill_comment_counts, CommentView};
+use crate::routes::collections::{fetch_collection_options, fetch_collection_subtree, CollectionOption};
+use crate::tag_filter::TagFilter;
 use crate::AppState;
 
 #[derive(Template)]
@@ -20,6 +27,18 @@ use crate::AppState;
 struct EntryListTemplate {
     entries: Vec<EntryView>,
     filter: String,
+    query: String,
+    /// Empty when not filtering by priority
+    priority: String,
+    /// Tag filter as shown in the search form
+    tags: String,
+    /// Empty when not filtering by collection
+    collection: String,
+    collections: Vec<CollectionOption>,
+    tag_chips: Vec<TagChip>,
+    /// Current search and filters as a query string (with leading `?`), kept
+    /// when switching between Ready/Waiting/Unseen/All
+    query_string: String,
     static_hash: &'static str,
     user: Option<User>,
 }
@@ -30,15 +49,40 @@ struct EntryTemplate {
     entry: EntryView,
 }
 
+#[derive(Template)]
+#[template(path = "entries/show.html")]
+struct EntryShowTemplate {
+    entry: EntryView,
+    visits: Vec<VisitView>,
+    revisions: Vec<RevisionView>,
+    comments: Vec<CommentView>,
+    /// Only collection entries have comments
+    can_comment: bool,
+    can_edit: bool,
+    static_hash: &'static str,
+    user: Option<User>,
+}
+
+/// An active tag filter term, with a link to the same list without it
+struct TagChip {
+    label: String,
+    remove_href: String,
+}
+
 pub struct EntryView {
     pub id: String,
     pub url: String,
     pub title: String,
-    pub description: Option<String>,
+    /// Description rendered from Markdown and sanitized
+    pub description_html: Option<String>,
     pub last_viewed: Option<String>,
     pub available_in: Option<String>,
     pub is_available: bool,
     pub visit_count: i64,
+    /// Filled in by [`fill_comment_counts`] for entry cards
+    pub comment_count: i64,
+    pub priority: Priority,
+    pub pinned: bool,
 }
 
 /// Entry with visit count for queries that join entries with visits
@@ -56,10 +100,52 @@ pub struct EntryWithCount {
     dismissed_at: Option<String>,
     created_at: String,
     updated_at: String,
+    priority: Priority,
+    pinned: bool,
     // Extra field
     visit_count: i64,
 }
 
+/// A visit joined with the visitor's name, for the entry history
+#[derive(FromRow)]
+struct VisitWithUser {
+    id: String,
+    user_id: String,
+    user_name: String,
+    visited_at: String,
+    note: Option<String>,
+}
+
+struct VisitView {
+    id: String,
+    user_name: String,
+    visited: String,
+    note: Option<String>,
+    note_html: Option<String>,
+    is_own: bool,
+}
+
+/// A revision joined with the editor's name, for the entry's edit history
+#[derive(FromRow)]
+struct RevisionWithUser {
+    #[sqlx(flatten)]
+    revision: EntryRevision,
+    user_name: Option<String>,
+}
+
+struct RevisionView {
+    id: String,
+    user_name: String,
+    changed: String,
+    changes: Vec<ChangeView>,
+}
+
+struct ChangeView {
+    field: &'static str,
+    old: String,
+    new: String,
+}
+
 impl EntryWithCount {
     pub fn into_entry_and_count(self) -> (Entry, i64) {
         let entry = Entry {
@@ -74,6 +160,8 @@ impl EntryWithCount {
             dismissed_at: self.dismissed_at,
             created_at: self.created_at,
             updated_at: self.updated_at,
+            priority: self.priority,
+            pinned: self.pinned,
         };
         (entry, self.visit_count)
     }
@@ -84,12 +172,75 @@ impl EntryWithCount {
 struct EntryFormTemplate {
     entry: Option<Entry>,
     collections: Vec<Collection>,
-    tags_string: String,
+    values: EntryFormValues,
     errors: HashMap<String, String>,
     static_hash: &'static str,
     user: Option<User>,
 }
 
+/// Values shown in the entry form: an existing entry, a submission that
+/// failed validation, or a new entry pre-filled by quick-add
+struct EntryFormValues {
+    url: String,
+    title: String,
+    description: String,
+    duration: i64,
+    interval: String,
+    tags: String,
+    /// Empty for a private entry
+    collection_id: String,
+    priority: String,
+    pinned: bool,
+    schedule_set: bool,
+}
+
+impl EntryFormValues {
+    fn new_entry(settings: &UserSettings) -> Self {
+        Self {
+            url: String::new(),
+            title: String::new(),
+            description: String::new(),
+            duration: settings.default_duration,
+            interval: settings.default_interval.to_string(),
+            tags: settings.default_tags.clone(),
+            collection_id: settings.default_collection_id.clone().unwrap_or_default(),
+            priority: Priority::default().to_string(),
+            pinned: false,
+            schedule_set: false,
+        }
+    }
+
+    fn from_entry(entry: &Entry, tags: &[String]) -> Self {
+        Self {
+            url: entry.url.clone(),
+            title: entry.title.clone(),
+            description: entry.description.clone().unwrap_or_default(),
+            duration: entry.duration,
+            interval: entry.interval.to_string(),
+            tags: tags.join(", "),
+            collection_id: entry.collection_id.clone().unwrap_or_default(),
+            priority: entry.priority.to_string(),
+            pinned: entry.pinned,
+            schedule_set: true,
+        }
+    }
+
+    fn from_form(form: &EntryForm) -> Self {
+        Self {
+            url: form.url.clone(),
+            title: form.title.clone(),
+            description: form.description.clone().unwrap_or_default(),
+            duration: form.duration,
+            interval: form.interval.to_string(),
+            tags: form.tags.clone().unwrap_or_default(),
+            collection_id: form.collection_id.clone().unwrap_or_default(),
+            priority: form.priority.to_string(),
+            pinned: form.pinned,
+            schedule_set: form.schedule_set,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct EntryForm {
     url: String,
@@ -99,11 +250,53 @@ pub struct EntryForm {
     interval: Interval,
     tags: Option<String>,
     collection_id: Option<String>,
+    #[serde(default)]
+    priority: Priority,
+    /// Checkbox: only sent when checked
+    #[serde(default)]
+    pinned: bool,
+    /// Set by the new entry form once the user picks a schedule; until then
+    /// a tag's or the collection's default schedule replaces the form's
+    #[serde(default)]
+    schedule_set: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    q: Option<String>,
+    /// `low`, `normal` or `high`; anything else shows every priority
+    priority: Option<String>,
+    /// Only show links in this collection and its subcollections
+    collection: Option<String>,
+}
+
+/// Link details sent by the bookmarklet or another client. Everything but
+/// the URL is optional; the schedule comes from the tags' default schedule
+/// or else the user's defaults.
+#[derive(Deserialize)]
+pub struct QuickAddForm {
+    url: Option<String>,
+    title: Option<String>,
+    tags: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct VisitForm {
+    note: Option<String>,
+}
+
+impl VisitForm {
+    fn check_note_length(&self) -> Result<(), AppError> {
+        if self.note.as_ref().is_some_and(|n| n.len() > MAX_NOTE_LEN) {
+            return Err(AppError::BadRequest("Note must be under 5000 characters".to_string()));
+        }
+        Ok(())
+    }
 }
 
 /// Normalizes a URL string: prepends `https://` if no scheme, then parses with
 /// the `url` crate. Returns `Ok(normalized_url_string)` or `Err(error_message)`.
-fn normalize_url(raw: &str) -> Result<String, String> {
+pub fn normalize_url(raw: &str) -> Result<String, String> {
     let raw = raw.trim();
     if raw.is_empty() {
         return Ok(String::new());
@@ -131,6 +324,9 @@ fn normalize_url(raw: &str) -> Result<String, String> {
     }
 }
 
+/// Longest visit note or comment the server accepts, as for descriptions
+pub const MAX_NOTE_LEN: usize = 5000;
+
 fn validate_entry_form(form: &EntryForm) -> HashMap<String, String> {
     let mut errors = HashMap::new();
 
@@ -153,7 +349,7 @@ fn validate_entry_form(form: &EntryForm) -> HashMap<String, String> {
     }
 
     if let Some(ref desc) = form.description {
-        if desc.len() > 5000 {
+        if desc.len() > MAX_NOTE_LEN {
             errors.insert("description".to_string(), "Description must be under 5000 characters".to_string());
         }
     }
@@ -169,13 +365,19 @@ pub fn router() -> Router<AppState> {
         .route("/unseen", get(list_unseen_entries))
         .route("/entries/new", get(new_entry_form))
         .route("/entries", post(create_entry))
+        .route("/entries/quick-add", get(quick_add_form))
+        .route("/entries/quick-add", post(quick_add_entry))
+        .route("/entries/{id}", get(show_entry))
         .route("/entries/{id}/edit", get(edit_entry_form))
         .route("/entries/{id}", post(update_entry))
         .route("/entries/{id}", delete(delete_entry))
         .route("/entries/{id}/visit", post(visit_entry))
+        .route("/entries/{id}/visits", post(add_visit))
+        .route("/entries/{id}/visits/{visit_id}", post(update_visit_note))
+        .route("/entries/{id}/revisions/{revision_id}/revert", post(revert_revision))
 }
 
-fn calculate_availability(entry: &Entry, now: DateTime<Utc>) -> (bool, Option<String>) {
+pub fn calculate_availability(entry: &Entry, now: DateTime<Utc>) -> (bool, Option<String>) {
     let Some(dismissed_at) = &entry.dismissed_at else {
         return (true, None);
     };
@@ -222,7 +424,7 @@ fn calculate_availability(entry: &Entry, now: DateTime<Utc>) -> (bool, Option<St
     }
 }
 
-fn format_last_viewed(dismissed_at: &Option<String>, now: DateTime<Utc>) -> Option<String> {
+pub fn format_last_viewed(dismissed_at: &Option<String>, now: DateTime<Utc>) -> Option<String> {
     let dismissed_at = dismissed_at.as_ref()?;
     let dismissed: DateTime<Utc> = dismissed_at.parse().ok()?;
     let diff = now - dismissed;
@@ -281,10 +483,10 @@ async fn fetch_entries_for_user(db: &sqlx::SqlitePool, user_id: &str) -> Vec<(En
         FROM entries e
         LEFT JOIN visits v ON v.entry_id = e.id
         WHERE e.user_id = ? OR e.collection_id IN (
-            SELECT collection_id FROM collection_members WHERE user_id = ?
+            SELECT collection_id FROM collection_access WHERE user_id = ?
         )
         GROUP BY e.id
-        ORDER BY e.dismissed_at DESC NULLS FIRST
+        ORDER BY e.pinned DESC, e.dismissed_at DESC NULLS FIRST
         "#
     )
     .bind(user_id)
@@ -296,42 +498,251 @@ async fn fetch_entries_for_user(db: &sqlx::SqlitePool, user_id: &str) -> Vec<(En
     entries.into_iter().map(|e| e.into_entry_and_count()).collect()
 }
 
+/// Tag names per entry, for every entry the user can see
+async fn fetch_tags_by_entry(db: &sqlx::SqlitePool, user_id: &str) -> HashMap<String, Vec<String>> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT et.entry_id, t.name
+        FROM entry_tags et
+        JOIN tags t ON t.id = et.tag_id
+        JOIN entries e ON e.id = et.entry_id
+        WHERE e.user_id = ? OR e.collection_id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ?
+        )
+        "#
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for (entry_id, name) in rows {
+        tags.entry(entry_id).or_default().push(name);
+    }
+    tags
+}
+
+/// Fetch a single entry the user can see (their own, or shared via a
+/// collection they own or belong to)
+pub async fn fetch_accessible_entry(
+    db: &sqlx::SqlitePool,
+    id: &str,
+    user_id: &str,
+) -> Result<Option<Entry>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT * FROM entries WHERE id = ? AND (user_id = ? OR collection_id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ?
+        ))
+        "#
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// An entry the user may edit or delete: their own private entry, or an
+/// entry in a collection where they're an editor, admin or the owner
+async fn fetch_editable_entry(db: &sqlx::SqlitePool, id: &str, user_id: &str) -> Result<Option<Entry>, sqlx::Error> {
+    let entry: Option<Entry> = sqlx::query_as("SELECT * FROM entries WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await?;
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+
+    let can_edit = match &entry.collection_id {
+        Some(collection_id) => CollectionRole::load(db, collection_id, user_id)
+            .await?
+            .is_some_and(|role| role.can_edit_entries()),
+        None => entry.user_id == user_id,
+    };
+    Ok(can_edit.then_some(entry))
+}
+
+/// Whether the user may take an entry out of its collection, into another
+/// one or their author's private links: only the author or an admin can
+async fn can_move_out_of_collection(db: &sqlx::SqlitePool, entry: &Entry, user_id: &str) -> Result<bool, sqlx::Error> {
+    let Some(collection_id) = &entry.collection_id else {
+        return Ok(true);
+    };
+    if entry.user_id == user_id {
+        return Ok(true);
+    }
+    let role = CollectionRole::load(db, collection_id, user_id).await?;
+    Ok(role.is_some_and(|r| r.can_manage_members()))
+}
+
+/// Reject a collection the user can't add entries to
+fn validate_entry_collection(form: &EntryForm, collections: &[Collection], errors: &mut HashMap<String, String>) {
+    let collection_id = form.collection_id.as_deref().unwrap_or("");
+    if !collection_id.is_empty() && !collections.iter().any(|c| c.id == collection_id) {
+        errors.insert(
+            "collection_id".to_string(),
+            "You can't add links to this collection".to_string(),
+        );
+    }
+}
+
+/// IDs of entries whose title, URL, description or visit notes match the search text
+async fn search_entry_ids(db: &sqlx::SqlitePool, query: &str) -> HashSet<String> {
+    let pattern = format!("%{}%", query);
+    let ids: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM entries WHERE title LIKE ? OR url LIKE ? OR description LIKE ?
+        UNION
+        SELECT entry_id FROM visits WHERE note LIKE ?
+        "#
+    )
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(&pattern)
+    .bind(&pattern)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default();
+
+    ids.into_iter().map(|(id,)| id).collect()
+}
+
 pub fn build_entry_view(entry: Entry, visit_count: i64, now: DateTime<Utc>) -> EntryView {
     let (is_available, available_in) = calculate_availability(&entry, now);
     EntryView {
+        description_html: markdown::render_optional(entry.description.as_deref()),
         id: entry.id,
         url: entry.url,
         title: entry.title,
-        description: entry.description,
         last_viewed: format_last_viewed(&entry.dismissed_at, now),
         available_in,
         is_available,
         visit_count,
+        comment_count: 0,
+        priority: entry.priority,
+        pinned: entry.pinned,
+    }
+}
+
+/// Pinned entries first, then (for the Ready list) higher priority first.
+/// The sort is stable, so ties keep the order they were fetched in.
+fn sort_entry_views(entries: &mut [EntryView], filter: &str) {
+    entries.sort_by_key(|ev| {
+        let priority = if filter == "ready" { ev.priority } else { Priority::Normal };
+        (std::cmp::Reverse(ev.pinned), std::cmp::Reverse(priority))
+    });
+}
+
+/// Whether an entry belongs in a list view: `ready`, `waiting`, `unseen` or `all`
+pub fn matches_view_filter(ev: &EntryView, filter: &str) -> bool {
+    match filter {
+        // Pinning only orders a list (see `sort_entry_views`), it doesn't
+        // add entries to it
+        "ready" => ev.is_available,
+        "waiting" => !ev.is_available,
+        "unseen" => ev.visit_count == 0,
+        _ => true, // "all"
+    }
+}
+
+fn filter_path(filter: &str) -> String {
+    match filter {
+        "ready" => "/".to_string(),
+        other => format!("/{}", other),
+    }
+}
+
+/// Query string for a list view's search and filters, empty if there are none
+fn list_query_string(search: &str, priority: &str, collection: &str, tags: &TagFilter) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    if !search.is_empty() {
+        serializer.append_pair("q", search);
+    }
+    if !priority.is_empty() {
+        serializer.append_pair("priority", priority);
+    }
+    if !collection.is_empty() {
+        serializer.append_pair("collection", collection);
     }
+    if !tags.is_empty() {
+        let terms: Vec<String> = tags.terms.iter().map(|t| t.label()).collect();
+        serializer.append_pair("tags", &terms.join(","));
+    }
+    let query = serializer.finish();
+    if query.is_empty() { query } else { format!("?{}", query) }
 }
 
 async fn list_filtered_entries(
     db: &sqlx::SqlitePool,
     user: User,
     filter: &str,
+    query: ListQuery,
+    tag_filter: TagFilter,
 ) -> Result<Html<String>, AppError> {
-    let entries = fetch_entries_for_user(db, &user.id).await;
+    let mut entries = fetch_entries_for_user(db, &user.id).await;
     let now = Utc::now();
 
-    let entry_views: Vec<EntryView> = entries
+    let search = query.q.map(|q| q.trim().to_string()).unwrap_or_default();
+    if !search.is_empty() {
+        let matching = search_entry_ids(db, &search).await;
+        entries.retain(|(entry, _)| matching.contains(&entry.id));
+    }
+
+    let priority = query.priority.and_then(|p| p.parse::<Priority>().ok());
+    if let Some(priority) = priority {
+        entries.retain(|(entry, _)| entry.priority == priority);
+    }
+
+    let collection = query.collection.filter(|c| !c.is_empty()).unwrap_or_default();
+    if !collection.is_empty() {
+        let subtree = fetch_collection_subtree(db, &collection).await?;
+        entries.retain(|(entry, _)| entry.collection_id.as_ref().is_some_and(|c| subtree.contains(c)));
+    }
+
+    if !tag_filter.is_empty() {
+        let tags_by_entry = fetch_tags_by_entry(db, &user.id).await;
+        entries.retain(|(entry, _)| {
+            let tags = tags_by_entry.get(&entry.id).map(|t| t.as_slice()).unwrap_or_default();
+            tag_filter.matches(tags)
+        });
+    }
+
+    let mut entry_views: Vec<EntryView> = entries
         .into_iter()
         .map(|(entry, visit_count)| build_entry_view(entry, visit_count, now))
-        .filter(|ev| match filter {
-            "ready" => ev.is_available,
-            "waiting" => !ev.is_available,
-            "unseen" => ev.visit_count == 0,
-            _ => true, // "all"
+        .filter(|ev| matches_view_filter(ev, filter))
+        .collect();
+    sort_entry_views(&mut entry_views, filter);
+    fill_comment_counts(db, &user.id, &mut entry_views).await;
+
+    let priority = priority.map(|p| p.to_string()).unwrap_or_default();
+    let tag_chips = tag_filter
+        .terms
+        .iter()
+        .enumerate()
+        .map(|(i, term)| TagChip {
+            label: term.label(),
+            remove_href: format!(
+                "{}{}",
+                filter_path(filter),
+                list_query_string(&search, &priority, &collection, &tag_filter.without(i))
+            ),
         })
         .collect();
 
     let template = EntryListTemplate {
         entries: entry_views,
         filter: filter.to_string(),
+        query_string: list_query_string(&search, &priority, &collection, &tag_filter),
+        tags: tag_filter.to_input_value(),
+        collections: fetch_collection_options(db, &user.id, CollectionRole::Viewer).await?,
+        collection,
+        tag_chips,
+        query: search,
+        priority,
         static_hash: crate::STATIC_HASH,
         user: Some(user),
     };
@@ -341,265 +752,785 @@ async fn list_filtered_entries(
 async fn list_entries(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
+    Query(query): Query<ListQuery>,
+    RawQuery(raw_query): RawQuery,
 ) -> Result<impl IntoResponse, AppError> {
-    list_filtered_entries(&state.db, user, "ready").await
+    let tags = TagFilter::from_query(raw_query.as_deref());
+    list_filtered_entries(&state.db, user, "ready", query, tags).await
 }
 
 async fn list_all_entries(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
+    Query(query): Query<ListQuery>,
+    RawQuery(raw_query): RawQuery,
 ) -> Result<impl IntoResponse, AppError> {
-    list_filtered_entries(&state.db, user, "all").await
+    let tags = TagFilter::from_query(raw_query.as_deref());
+    list_filtered_entries(&state.db, user, "all", query, tags).await
 }
 
 async fn list_waiting_entries(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
+    Query(query): Query<ListQuery>,
+    RawQuery(raw_query): RawQuery,
 ) -> Result<impl IntoResponse, AppError> {
-    list_filtered_entries(&state.db, user, "waiting").await
+    let tags = TagFilter::from_query(raw_query.as_deref());
+    list_filtered_entries(&state.db, user, "waiting", query, tags).await
 }
 
 async fn list_unseen_entries(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
+    Query(query): Query<ListQuery>,
+    RawQuery(raw_query): RawQuery,
 ) -> Result<impl IntoResponse, AppError> {
-    list_filtered_entries(&state.db, user, "unseen").await
+    let tags = TagFilter::from_query(raw_query.as_deref());
+    list_filtered_entries(&state.db, user, "unseen", query, tags).await
 }
 
-async fn visit_entry(
-    State(state): State<AppState>,
-    AuthUser(user): AuthUser,
-    Path(id): Path<String>,
-) -> Result<impl IntoResponse, AppError> {
-    // Verify user has access to this entry
-    let entry: Option<Entry> = sqlx::query_as(
-        r#"
-        SELECT * FROM entries WHERE id = ? AND (user_id = ? OR collection_id IN (
-            SELECT collection_id FROM collection_members WHERE user_id = ?
-        ))
-        "#
-    )
-    .bind(&id)
-    .bind(&user.id)
-    .bind(&user.id)
-    .fetch_optional(&state.db)
-    .await?;
-
-    let Some(mut entry) = entry else {
-        return Err(AppError::NotFound);
-    };
-
-    let now = Utc::now().to_rfc3339();
+/// Insert a visit (with an optional note) and mark the entry as dismissed now
+async fn record_visit(
+    db: &sqlx::SqlitePool,
+    entry: &mut Entry,
+    user_id: &str,
+    note: Option<String>,
+) -> Result<(), AppError> {
+    let mut visit = Visit::new(entry.id.clone(), user_id.to_string());
+    visit.note = note.filter(|n| !n.trim().is_empty());
 
-    // Create visit record
-    let visit = Visit::new(id.clone(), user.id.clone());
     sqlx::query(
-        "INSERT INTO visits (id, entry_id, user_id, visited_at) VALUES (?, ?, ?, ?)"
+        "INSERT INTO visits (id, entry_id, user_id, visited_at, note) VALUES (?, ?, ?, ?, ?)"
     )
     .bind(&visit.id)
     .bind(&visit.entry_id)
     .bind(&visit.user_id)
     .bind(&visit.visited_at)
-    .execute(&state.db)
+    .bind(&visit.note)
+    .execute(db)
     .await?;
 
     // Update entry dismissed_at
     sqlx::query("UPDATE entries SET dismissed_at = ?, updated_at = ? WHERE id = ?")
-        .bind(&now)
-        .bind(&now)
-        .bind(&id)
-        .execute(&state.db)
+        .bind(&visit.visited_at)
+        .bind(&visit.visited_at)
+        .bind(&entry.id)
+        .execute(db)
         .await?;
 
+    if let Some(event) = CollectionEvent::for_entry(EventKind::Visited, user_id, entry) {
+        event.record(db).await?;
+    }
+
     // Update local entry for correct availability calculation
-    entry.dismissed_at = Some(now);
+    entry.dismissed_at = Some(visit.visited_at);
+    Ok(())
+}
+
+async fn visit_entry(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    // Verify user has access to this entry
+    let Some(mut entry) = fetch_accessible_entry(&state.db, &id, &user.id).await? else {
+        return Err(AppError::NotFound);
+    };
+
+    record_visit(&state.db, &mut entry, &user.id, None).await?;
 
     let visit_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM visits WHERE entry_id = ?")
         .bind(&id)
         .fetch_one(&state.db)
         .await?;
 
-    let now_dt = Utc::now();
-    let (is_available, available_in) = calculate_availability(&entry, now_dt);
-
-    let template = EntryTemplate {
-        entry: EntryView {
-            id: entry.id,
-            url: entry.url,
-            title: entry.title,
-            description: entry.description,
-            last_viewed: format_last_viewed(&entry.dismissed_at, now_dt),
-            available_in,
-            is_available,
-            visit_count: visit_count.0,
-        },
-    };
+    let mut entry = build_entry_view(entry, visit_count.0, Utc::now());
+    fill_comment_counts(&state.db, &user.id, std::slice::from_mut(&mut entry)).await;
+
+    let template = EntryTemplate { entry };
     Ok(Html(template.render()?))
 }
 
-async fn new_entry_form(
+async fn show_entry(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
+    Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    let collections: Vec<Collection> = sqlx::query_as(
+    let Some(entry) = fetch_accessible_entry(&state.db, &id, &user.id).await? else {
+        return Ok(Redirect::to("/").into_response());
+    };
+
+    let visits: Vec<VisitWithUser> = sqlx::query_as(
         r#"
-        SELECT c.* FROM collections c
-        LEFT JOIN collection_members cm ON cm.collection_id = c.id
-        WHERE c.owner_id = ? OR cm.user_id = ?
+        SELECT v.id, v.user_id, u.name as user_name, v.visited_at, v.note
+        FROM visits v
+        JOIN users u ON u.id = v.user_id
+        WHERE v.entry_id = ?
+        ORDER BY v.visited_at DESC
         "#
     )
-    .bind(&user.id)
-    .bind(&user.id)
+    .bind(&id)
     .fetch_all(&state.db)
-    .await
-    .unwrap_or_default();
-
-    let template = EntryFormTemplate {
-        entry: None,
-        collections,
-        tags_string: String::new(),
-        errors: HashMap::new(),
-        static_hash: crate::STATIC_HASH,
-        user: Some(user),
-    };
-    Ok(Html(template.render()?))
-}
-
-async fn create_entry(
-    State(state): State<AppState>,
-    AuthUser(user): AuthUser,
-    Form(form): Form<EntryForm>,
-) -> Result<impl IntoResponse, AppError> {
-    let errors = validate_entry_form(&form);
-    if !errors.is_empty() {
-        let collections: Vec<Collection> = sqlx::query_as(
-            r#"
-            SELECT c.* FROM collections c
-            LEFT JOIN collection_members cm ON cm.collection_id = c.id
-            WHERE c.owner_id = ? OR cm.user_id = ?
-            "#
-        )
-        .bind(&user.id)
-        .bind(&user.id)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default();
-
-        let template = EntryFormTemplate {
-            entry: None,
-            collections,
-            tags_string: form.tags.as_deref().unwrap_or("").to_string(),
-            errors,
-            static_hash: crate::STATIC_HASH,
-            user: Some(user),
-        };
-        return Ok(Html(template.render()?).into_response());
-    }
-
-    let now = chrono::Utc::now().to_rfc3339();
-    let id = uuid::Uuid::new_v4().to_string();
+    .await?;
 
-    let collection_id = form.collection_id.filter(|s| !s.is_empty());
+    let now = Utc::now();
+    let visits: Vec<VisitView> = visits
+        .into_iter()
+        .map(|v| VisitView {
+            visited: format_last_viewed(&Some(v.visited_at), now).unwrap_or_default(),
+            note_html: markdown::render_optional(v.note.as_deref()),
+            is_own: v.user_id == user.id,
+            id: v.id,
+            user_name: v.user_name,
+            note: v.note,
+        })
+        .collect();
 
-    sqlx::query(
+    let revisions: Vec<RevisionWithUser> = sqlx::query_as(
         r#"
-        INSERT INTO entries (id, user_id, collection_id, url, title, description, duration, interval, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        SELECT r.*, u.name as user_name
+        FROM entry_revisions r
+        LEFT JOIN users u ON u.id = r.user_id
+        WHERE r.entry_id = ?
+        ORDER BY r.created_at DESC
         "#
     )
     .bind(&id)
-    .bind(&user.id)
-    .bind(&collection_id)
-    .bind(&normalize_url(&form.url).unwrap())
-    .bind(&form.title)
-    .bind(&form.description)
-    .bind(form.duration)
-    .bind(&form.interval)
-    .bind(&now)
-    .bind(&now)
-    .execute(&state.db)
+    .fetch_all(&state.db)
     .await?;
 
-    // Handle tags
-    if let Some(tags) = form.tags {
-        for tag_name in tags.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()) {
-            // Get or create tag
-            let tag_id: Option<(String,)> = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
-                .bind(&tag_name)
-                .fetch_optional(&state.db)
-                .await?;
-
-            let tag_id = match tag_id {
-                Some((id,)) => id,
-                None => {
-                    let new_id = uuid::Uuid::new_v4().to_string();
-                    sqlx::query("INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)")
-                        .bind(&new_id)
-                        .bind(&tag_name)
-                        .bind(&now)
-                        .execute(&state.db)
-                        .await?;
-                    new_id
-                }
+    let mut revision_views = Vec::with_capacity(revisions.len());
+    for r in revisions {
+        let mut changes = Vec::new();
+        for change in r.revision.field_changes() {
+            let (old, new) = if change.field == "collection_id" {
+                (
+                    collection_label(&state.db, change.old.as_deref()).await,
+                    collection_label(&state.db, change.new.as_deref()).await,
+                )
+            } else if change.field == "pinned" {
+                (pinned_label(change.old.as_deref()), pinned_label(change.new.as_deref()))
+            } else {
+                (display_value(change.old.as_deref()), display_value(change.new.as_deref()))
             };
+            changes.push(ChangeView {
+                field: field_label(&change.field),
+                old,
+                new,
+            });
+        }
+        revision_views.push(RevisionView {
+            changed: format_last_viewed(&Some(r.revision.created_at), now).unwrap_or_default(),
+            user_name: r.user_name.unwrap_or_else(|| "Deleted user".to_string()),
+            id: r.revision.id,
+            changes,
+        });
+    }
+
+    let can_comment = entry.collection_id.is_some();
+    let comments = if can_comment {
+        fetch_comment_views(&state.db, &id, &user.id, now).await?
+    } else {
+        vec![]
+    };
+    let mut entry = build_entry_view(entry, visits.len() as i64, now);
+    entry.comment_count = comments.iter().filter(|c| !c.is_deleted).count() as i64;
+
+    let template = EntryShowTemplate {
+        can_edit: fetch_editable_entry(&state.db, &id, &user.id).await?.is_some(),
+        entry,
+        visits,
+        comments,
+        can_comment,
+        revisions: revision_views,
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?).into_response())
+}
+
+fn field_label(field: &str) -> &'static str {
+    match field {
+        "url" => "URL",
+        "title" => "Title",
+        "description" => "Description",
+        "duration" => "Revisit every",
+        "interval" => "Interval",
+        "collection_id" => "Collection",
+        "priority" => "Priority",
+        "pinned" => "Pinned",
+        "tags" => "Tags",
+        _ => "Other",
+    }
+}
 
-            // Link tag to entry
-            sqlx::query("INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
-                .bind(&id)
-                .bind(&tag_id)
-                .execute(&state.db)
-                .await?;
+/// Shorten a revision value for display; long descriptions are truncated
+fn display_value(value: Option<&str>) -> String {
+    const MAX_CHARS: usize = 80;
+    match value {
+        None => "(empty)".to_string(),
+        Some(v) if v.chars().count() > MAX_CHARS => {
+            format!("{}…", v.chars().take(MAX_CHARS).collect::<String>())
         }
+        Some(v) => v.to_string(),
     }
+}
 
-    Ok(Redirect::to("/").into_response())
+fn pinned_label(value: Option<&str>) -> String {
+    if value == Some("true") { "Yes" } else { "No" }.to_string()
 }
 
-async fn edit_entry_form(
+async fn collection_label(db: &sqlx::SqlitePool, collection_id: Option<&str>) -> String {
+    let Some(collection_id) = collection_id else {
+        return "Private".to_string();
+    };
+    let name: Option<(String,)> = sqlx::query_as("SELECT name FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_optional(db)
+        .await
+        .unwrap_or_default();
+    name.map(|(n,)| n).unwrap_or_else(|| "Deleted collection".to_string())
+}
+
+async fn add_visit(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Path(id): Path<String>,
+    Form(form): Form<VisitForm>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Verify user owns this entry
-    let entry: Option<Entry> = sqlx::query_as(
-        "SELECT * FROM entries WHERE id = ? AND user_id = ?"
-    )
-    .bind(&id)
-    .bind(&user.id)
-    .fetch_optional(&state.db)
-    .await?;
-
-    let Some(entry) = entry else {
-        return Ok(Redirect::to("/").into_response());
+    let Some(mut entry) = fetch_accessible_entry(&state.db, &id, &user.id).await? else {
+        return Ok(Redirect::to("/"));
     };
+    form.check_note_length()?;
 
-    let collections: Vec<Collection> = sqlx::query_as(
-        r#"
-        SELECT c.* FROM collections c
-        LEFT JOIN collection_members cm ON cm.collection_id = c.id
-        WHERE c.owner_id = ? OR cm.user_id = ?
-        "#
-    )
-    .bind(&user.id)
-    .bind(&user.id)
-    .fetch_all(&state.db)
+    record_visit(&state.db, &mut entry, &user.id, form.note).await?;
+
+    Ok(Redirect::to(&format!("/entries/{}", id)))
+}
+
+async fn update_visit_note(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((id, visit_id)): Path<(String, String)>,
+    Form(form): Form<VisitForm>,
+) -> Result<impl IntoResponse, AppError> {
+    form.check_note_length()?;
+    let note = form.note.filter(|n| !n.trim().is_empty());
+
+    // Only the visitor can change the note on their own visit
+    sqlx::query("UPDATE visits SET note = ? WHERE id = ? AND entry_id = ? AND user_id = ?")
+        .bind(&note)
+        .bind(&visit_id)
+        .bind(&id)
+        .bind(&user.id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Redirect::to(&format!("/entries/{}", id)))
+}
+
+/// Collections the user can add entries to (as owner, editor or admin), for
+/// the entry form's collection picker
+pub async fn fetch_user_collections(db: &sqlx::SqlitePool, user_id: &str) -> Vec<Collection> {
+    sqlx::query_as(
+        r#"
+        SELECT c.* FROM collections c
+        WHERE c.id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ? AND role IN ('editor', 'admin', 'owner')
+        )
+        ORDER BY c.name
+        "#
+    )
+    .bind(user_id)
+    .fetch_all(db)
     .await
-    .unwrap_or_default();
+    .unwrap_or_default()
+}
+
+/// Split a comma-separated tags field into lowercase, de-duplicated tag names.
+/// Hierarchical names are tidied so `Rust / Async/` becomes `rust/async`.
+pub fn parse_tags(raw: Option<&str>) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw
+        .unwrap_or("")
+        .split(',')
+        .map(|s| {
+            s.split('/')
+                .map(|segment| segment.trim().to_lowercase())
+                .filter(|segment| !segment.is_empty())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .filter(|s| !s.is_empty())
+    {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
 
+async fn fetch_entry_tags(db: &sqlx::SqlitePool, entry_id: &str) -> Result<Vec<String>, sqlx::Error> {
     let tags: Vec<(String,)> = sqlx::query_as(
         "SELECT t.name FROM tags t JOIN entry_tags et ON et.tag_id = t.id WHERE et.entry_id = ?"
     )
-    .bind(&id)
-    .fetch_all(&state.db)
+    .bind(entry_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(tags.into_iter().map(|(name,)| name).collect())
+}
+
+/// Replace an entry's tags, creating any tag rows that don't exist yet in
+/// the entry's namespace (its collection if shared, otherwise its owner).
+/// Runs in one transaction, so a failed save leaves the old tags in place.
+async fn set_entry_tags(db: &sqlx::SqlitePool, entry_id: &str, tags: &[String]) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let (user_id, collection_id): (String, Option<String>) =
+        sqlx::query_as("SELECT user_id, collection_id FROM entries WHERE id = ?")
+            .bind(entry_id)
+            .fetch_one(&mut *tx)
+            .await?;
+    let namespace = TagNamespace::for_entry(&user_id, collection_id.as_deref());
+    let old_tag_ids = fetch_entry_tag_ids(&mut *tx, entry_id).await?;
+
+    sqlx::query("DELETE FROM entry_tags WHERE entry_id = ?")
+        .bind(entry_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for tag_name in tags {
+        // Get or create tag
+        let tag_id: Option<(String,)> = sqlx::query_as(
+            "SELECT id FROM tags WHERE name = ? AND user_id IS ? AND collection_id IS ?"
+        )
+        .bind(tag_name)
+        .bind(namespace.user_id())
+        .bind(namespace.collection_id())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let tag_id = match tag_id {
+            Some((id,)) => id,
+            None => {
+                let tag = Tag::new(tag_name.clone(), &namespace);
+                sqlx::query("INSERT INTO tags (id, name, user_id, collection_id, created_at) VALUES (?, ?, ?, ?, ?)")
+                    .bind(&tag.id)
+                    .bind(&tag.name)
+                    .bind(&tag.user_id)
+                    .bind(&tag.collection_id)
+                    .bind(&tag.created_at)
+                    .execute(&mut *tx)
+                    .await?;
+                tag.id
+            }
+        };
+
+        // Link tag to entry
+        sqlx::query("INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
+            .bind(entry_id)
+            .bind(&tag_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    prune_unused_tags(&mut tx, &old_tag_ids).await?;
+    tx.commit().await
+}
+
+async fn fetch_entry_tag_ids(db: impl sqlx::SqliteExecutor<'_>, entry_id: &str) -> Result<Vec<String>, sqlx::Error> {
+    let ids: Vec<(String,)> = sqlx::query_as("SELECT tag_id FROM entry_tags WHERE entry_id = ?")
+        .bind(entry_id)
+        .fetch_all(db)
+        .await?;
+    Ok(ids.into_iter().map(|(id,)| id).collect())
+}
+
+/// Drop those of `tag_ids` no entry uses any more, e.g. an entry's old tags
+/// after a retag, a move between collections or a delete. Tags with a
+/// default schedule are kept so the schedule still applies the next time
+/// the tag is used.
+async fn prune_unused_tags(conn: &mut sqlx::SqliteConnection, tag_ids: &[String]) -> Result<(), sqlx::Error> {
+    for tag_id in tag_ids {
+        sqlx::query(
+            r#"
+            DELETE FROM tags WHERE id = ? AND default_duration IS NULL
+            AND NOT EXISTS (SELECT 1 FROM entry_tags WHERE tag_id = tags.id)
+            "#
+        )
+        .bind(tag_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Tags in a namespace that carry a default schedule
+pub async fn fetch_tag_schedules(db: &sqlx::SqlitePool, namespace: &TagNamespace) -> Result<Vec<Tag>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT * FROM tags WHERE user_id IS ? AND collection_id IS ? AND default_duration IS NOT NULL AND default_interval IS NOT NULL"
+    )
+    .bind(namespace.user_id())
+    .bind(namespace.collection_id())
+    .fetch_all(db)
     .await
-    .unwrap_or_default();
+}
+
+/// The default schedule for an entry with `tags`: that of the first tag with
+/// one, where a tag without its own schedule uses its nearest ancestor's
+/// (`news/tech` falls back to `news`).
+pub fn tag_schedule(schedules: &[Tag], tags: &[String]) -> Option<(i64, Interval)> {
+    tags.iter().find_map(|tag| {
+        let mut name = tag.as_str();
+        loop {
+            if let Some(schedule) = schedules.iter().find(|t| t.name == name).and_then(|t| t.schedule()) {
+                return Some(schedule);
+            }
+            name = &name[..name.rfind('/')?];
+        }
+    })
+}
+
+/// Fill in a new entry's defaults. An entry added to a collection gets the
+/// collection's default tags. Unless the user picked a schedule, the entry
+/// takes its tags' default schedule, else the collection's.
+async fn apply_entry_defaults(db: &sqlx::SqlitePool, user_id: &str, form: &mut EntryForm) -> Result<(), sqlx::Error> {
+    let collection_id = form.collection_id.clone().filter(|s| !s.is_empty());
+    let collection: Option<Collection> = match &collection_id {
+        Some(id) => sqlx::query_as("SELECT * FROM collections WHERE id = ?")
+            .bind(id)
+            .fetch_optional(db)
+            .await?,
+        None => None,
+    };
+
+    if let Some(collection) = &collection {
+        let combined = format!("{},{}", form.tags.as_deref().unwrap_or(""), collection.default_tags);
+        form.tags = Some(parse_tags(Some(&combined)).join(", "));
+    }
+
+    if form.schedule_set {
+        return Ok(());
+    }
+
+    let namespace = TagNamespace::for_entry(user_id, collection_id.as_deref());
+    let schedules = fetch_tag_schedules(db, &namespace).await?;
+
+    let schedule = tag_schedule(&schedules, &parse_tags(form.tags.as_deref()))
+        .or_else(|| collection.and_then(|c| c.schedule()));
+    if let Some((duration, interval)) = schedule {
+        form.duration = duration;
+        form.interval = interval;
+    }
+    Ok(())
+}
+
+/// Write the editable fields of a snapshot back to an entry, including its tags
+async fn write_snapshot(db: &sqlx::SqlitePool, entry_id: &str, snapshot: &EntrySnapshot) -> Result<(), sqlx::Error> {
+    let now = chrono::Utc::now().to_rfc3339();
+
+    sqlx::query(
+        r#"
+        UPDATE entries
+        SET url = ?, title = ?, description = ?, duration = ?, interval = ?, collection_id = ?, priority = ?, pinned = ?, updated_at = ?
+        WHERE id = ?
+        "#
+    )
+    .bind(&snapshot.url)
+    .bind(&snapshot.title)
+    .bind(&snapshot.description)
+    .bind(snapshot.duration)
+    .bind(snapshot.interval)
+    .bind(&snapshot.collection_id)
+    .bind(snapshot.priority)
+    .bind(snapshot.pinned)
+    .bind(&now)
+    .bind(entry_id)
+    .execute(db)
+    .await?;
+
+    set_entry_tags(db, entry_id, &snapshot.tags).await
+}
+
+/// Store a revision for the change from `old` to `new`, if anything changed
+async fn record_revision(
+    db: &sqlx::SqlitePool,
+    entry_id: &str,
+    user_id: &str,
+    old: &EntrySnapshot,
+    new: &EntrySnapshot,
+) -> Result<(), sqlx::Error> {
+    let changes = old.diff(new);
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let revision = EntryRevision::new(entry_id.to_string(), user_id.to_string(), &changes);
+    sqlx::query(
+        "INSERT INTO entry_revisions (id, entry_id, user_id, changes, created_at) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(&revision.id)
+    .bind(&revision.entry_id)
+    .bind(&revision.user_id)
+    .bind(&revision.changes)
+    .bind(&revision.created_at)
+    .execute(db)
+    .await?;
+
+    record_edit_events(db, entry_id, user_id, old, new).await
+}
+
+/// Note an edit in the activity of the collections it touched. Moving an entry
+/// between collections shows as removed from one and added to the other.
+async fn record_edit_events(
+    db: &sqlx::SqlitePool,
+    entry_id: &str,
+    user_id: &str,
+    old: &EntrySnapshot,
+    new: &EntrySnapshot,
+) -> Result<(), sqlx::Error> {
+    let mut events = Vec::new();
+    if old.collection_id == new.collection_id {
+        events.extend(new.collection_id.clone().map(|id| (id, EventKind::EntryEdited)));
+    } else {
+        events.extend(old.collection_id.clone().map(|id| (id, EventKind::EntryRemoved)));
+        events.extend(new.collection_id.clone().map(|id| (id, EventKind::EntryAdded)));
+    }
+
+    for (collection_id, kind) in events {
+        let event = CollectionEvent::new(collection_id, user_id.to_string(), kind);
+        event.about_entry(entry_id, &new.title).record(db).await?;
+    }
+    Ok(())
+}
+
+/// Give every entry in a collection the same schedule, recording a revision
+/// for each entry that changes. Returns how many entries changed.
+pub async fn reschedule_collection_entries(
+    db: &sqlx::SqlitePool,
+    collection_id: &str,
+    user_id: &str,
+    duration: i64,
+    interval: Interval,
+) -> Result<usize, sqlx::Error> {
+    let entries: Vec<Entry> = sqlx::query_as(
+        "SELECT * FROM entries WHERE collection_id = ? AND (duration != ? OR interval != ?)"
+    )
+    .bind(collection_id)
+    .bind(duration)
+    .bind(interval)
+    .fetch_all(db)
+    .await?;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    for entry in &entries {
+        let old = EntrySnapshot::new(entry, fetch_entry_tags(db, &entry.id).await?);
+        let new = EntrySnapshot {
+            duration,
+            interval,
+            ..old.clone()
+        };
+
+        sqlx::query("UPDATE entries SET duration = ?, interval = ?, updated_at = ? WHERE id = ?")
+            .bind(duration)
+            .bind(interval)
+            .bind(&now)
+            .bind(&entry.id)
+            .execute(db)
+            .await?;
+        record_revision(db, &entry.id, user_id, &old, &new).await?;
+    }
+
+    Ok(entries.len())
+}
+
+/// Insert a validated entry form for the user, returning the new entry's id
+async fn insert_entry(db: &sqlx::SqlitePool, user_id: &str, form: EntryForm) -> Result<String, AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let id = uuid::Uuid::new_v4().to_string();
+
+    let collection_id = form.collection_id.filter(|s| !s.is_empty());
+
+    sqlx::query(
+        r#"
+        INSERT INTO entries (id, user_id, collection_id, url, title, description, duration, interval, priority, pinned, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(&collection_id)
+    .bind(normalize_url(&form.url).unwrap())
+    .bind(&form.title)
+    .bind(&form.description)
+    .bind(form.duration)
+    .bind(form.interval)
+    .bind(form.priority)
+    .bind(form.pinned)
+    .bind(&now)
+    .bind(&now)
+    .execute(db)
+    .await?;
+
+    set_entry_tags(db, &id, &parse_tags(form.tags.as_deref())).await?;
+
+    if let Some(collection_id) = collection_id {
+        let event = CollectionEvent::new(collection_id, user_id.to_string(), EventKind::EntryAdded);
+        event.about_entry(&id, &form.title).record(db).await?;
+    }
+
+    Ok(id)
+}
+
+async fn new_entry_form(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+    let settings = UserSettings::load(&state.db, &user.id).await?;
 
-    let tags_string = tags.into_iter().map(|(name,)| name).collect::<Vec<_>>().join(", ");
+    let template = EntryFormTemplate {
+        entry: None,
+        collections,
+        values: EntryFormValues::new_entry(&settings),
+        errors: HashMap::new(),
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn create_entry(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Form(mut form): Form<EntryForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+    let mut errors = validate_entry_form(&form);
+    validate_entry_collection(&form, &collections, &mut errors);
+    if !errors.is_empty() {
+        let template = EntryFormTemplate {
+            entry: None,
+            collections,
+            values: EntryFormValues::from_form(&form),
+            errors,
+            static_hash: crate::STATIC_HASH,
+            user: Some(user),
+        };
+        return Ok(Html(template.render()?).into_response());
+    }
+
+    apply_entry_defaults(&state.db, &user.id, &mut form).await?;
+    insert_entry(&state.db, &user.id, form).await?;
+
+    Ok(Redirect::to("/").into_response())
+}
+
+/// Combine submitted tags with the user's default tags
+fn with_default_tags(tags: Option<&str>, settings: &UserSettings) -> String {
+    let combined = format!("{},{}", tags.unwrap_or(""), settings.default_tags);
+    parse_tags(Some(&combined)).join(", ")
+}
+
+/// Pre-fill the new entry form from query params, e.g. from the bookmarklet
+async fn quick_add_form(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<QuickAddForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+    let settings = UserSettings::load(&state.db, &user.id).await?;
+
+    let mut values = EntryFormValues::new_entry(&settings);
+    values.url = query.url.unwrap_or_default();
+    values.title = query.title.unwrap_or_default();
+    values.tags = with_default_tags(query.tags.as_deref(), &settings);
+
+    let template = EntryFormTemplate {
+        entry: None,
+        collections,
+        values,
+        errors: HashMap::new(),
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Save a link directly using the user's defaults. Falls back to the
+/// pre-filled form if the submitted link doesn't validate.
+async fn quick_add_entry(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Form(quick): Form<QuickAddForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let settings = UserSettings::load(&state.db, &user.id).await?;
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+
+    // Only use the default collection while the user still has access to it
+    let collection_id = settings
+        .default_collection_id
+        .clone()
+        .filter(|id| collections.iter().any(|c| &c.id == id));
+
+    let url = quick.url.unwrap_or_default();
+    let title = quick
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| url.clone());
+
+    let mut form = EntryForm {
+        url,
+        title,
+        description: None,
+        duration: settings.default_duration,
+        interval: settings.default_interval,
+        tags: Some(with_default_tags(quick.tags.as_deref(), &settings)),
+        collection_id,
+        priority: Priority::default(),
+        pinned: false,
+        schedule_set: false,
+    };
+
+    let errors = validate_entry_form(&form);
+    if !errors.is_empty() {
+        let template = EntryFormTemplate {
+            entry: None,
+            collections,
+            values: EntryFormValues::from_form(&form),
+            errors,
+            static_hash: crate::STATIC_HASH,
+            user: Some(user),
+        };
+        return Ok(Html(template.render()?).into_response());
+    }
+
+    apply_entry_defaults(&state.db, &user.id, &mut form).await?;
+    insert_entry(&state.db, &user.id, form).await?;
+
+    Ok(Redirect::to("/").into_response())
+}
+
+async fn edit_entry_form(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    // Verify user may edit this entry
+    let entry = fetch_editable_entry(&state.db, &id, &user.id).await?;
+
+    let Some(entry) = entry else {
+        return Ok(Redirect::to("/").into_response());
+    };
+
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+    let tags = fetch_entry_tags(&state.db, &id).await?;
 
     let template = EntryFormTemplate {
+        values: EntryFormValues::from_entry(&entry, &tags),
         entry: Some(entry),
         collections,
-        tags_string,
         errors: HashMap::new(),
         static_hash: crate::STATIC_HASH,
         user: Some(user),
@@ -613,38 +1544,27 @@ async fn update_entry(
     Path(id): Path<String>,
     Form(form): Form<EntryForm>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Verify user owns this entry
-    let entry: Option<Entry> = sqlx::query_as(
-        "SELECT * FROM entries WHERE id = ? AND user_id = ?"
-    )
-    .bind(&id)
-    .bind(&user.id)
-    .fetch_optional(&state.db)
-    .await?;
+    // Verify user may edit this entry
+    let entry = fetch_editable_entry(&state.db, &id, &user.id).await?;
 
     let Some(entry) = entry else {
         return Ok(Redirect::to("/").into_response());
     };
 
-    let errors = validate_entry_form(&form);
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+    let mut errors = validate_entry_form(&form);
+    // An entry can stay in its collection, or move to one the user can add to
+    if form.collection_id.as_deref().unwrap_or("") != entry.collection_id.as_deref().unwrap_or("") {
+        validate_entry_collection(&form, &collections, &mut errors);
+        if !can_move_out_of_collection(&state.db, &entry, &user.id).await? {
+            errors.insert("collection_id".to_string(), "Only the author or an admin can move this link".to_string());
+        }
+    }
     if !errors.is_empty() {
-        let collections: Vec<Collection> = sqlx::query_as(
-            r#"
-            SELECT c.* FROM collections c
-            LEFT JOIN collection_members cm ON cm.collection_id = c.id
-            WHERE c.owner_id = ? OR cm.user_id = ?
-            "#
-        )
-        .bind(&user.id)
-        .bind(&user.id)
-        .fetch_all(&state.db)
-        .await
-        .unwrap_or_default();
-
         let template = EntryFormTemplate {
             entry: Some(entry),
             collections,
-            tags_string: form.tags.as_deref().unwrap_or("").to_string(),
+            values: EntryFormValues::from_form(&form),
             errors,
             static_hash: crate::STATIC_HASH,
             user: Some(user),
@@ -652,63 +1572,83 @@ async fn update_entry(
         return Ok(Html(template.render()?).into_response());
     }
 
-    let now = chrono::Utc::now().to_rfc3339();
-    let collection_id = form.collection_id.filter(|s| !s.is_empty());
+    let old = EntrySnapshot::new(&entry, fetch_entry_tags(&state.db, &id).await?);
+    let mut new = EntrySnapshot {
+        url: normalize_url(&form.url).unwrap(),
+        title: form.title,
+        description: form.description.filter(|d| !d.is_empty()),
+        duration: form.duration,
+        interval: form.interval,
+        collection_id: form.collection_id.filter(|s| !s.is_empty()),
+        priority: form.priority,
+        pinned: form.pinned,
+        tags: parse_tags(form.tags.as_deref()),
+    };
+    new.tags.sort();
 
-    sqlx::query(
-        r#"
-        UPDATE entries
-        SET url = ?, title = ?, description = ?, duration = ?, interval = ?, collection_id = ?, updated_at = ?
-        WHERE id = ?
-        "#
+    write_snapshot(&state.db, &id, &new).await?;
+    record_revision(&state.db, &id, &user.id, &old, &new).await?;
+
+    Ok(Redirect::to("/").into_response())
+}
+
+async fn revert_revision(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path((id, revision_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    // Verify user may edit this entry
+    let entry = fetch_editable_entry(&state.db, &id, &user.id).await?;
+
+    let Some(entry) = entry else {
+        return Ok(Redirect::to("/"));
+    };
+
+    let revision: Option<EntryRevision> = sqlx::query_as(
+        "SELECT * FROM entry_revisions WHERE id = ? AND entry_id = ?"
     )
-    .bind(&normalize_url(&form.url).unwrap())
-    .bind(&form.title)
-    .bind(&form.description)
-    .bind(form.duration)
-    .bind(&form.interval)
-    .bind(&collection_id)
-    .bind(&now)
+    .bind(&revision_id)
     .bind(&id)
-    .execute(&state.db)
+    .fetch_optional(&state.db)
     .await?;
 
-    // Clear existing tags and re-add
-    sqlx::query("DELETE FROM entry_tags WHERE entry_id = ?")
-        .bind(&id)
-        .execute(&state.db)
-        .await?;
+    let Some(revision) = revision else {
+        return Ok(Redirect::to(&format!("/entries/{}", id)));
+    };
 
-    if let Some(tags) = form.tags {
-        for tag_name in tags.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()) {
-            let tag_id: Option<(String,)> = sqlx::query_as("SELECT id FROM tags WHERE name = ?")
-                .bind(&tag_name)
-                .fetch_optional(&state.db)
-                .await?;
-
-            let tag_id = match tag_id {
-                Some((id,)) => id,
-                None => {
-                    let new_id = uuid::Uuid::new_v4().to_string();
-                    sqlx::query("INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)")
-                        .bind(&new_id)
-                        .bind(&tag_name)
-                        .bind(&now)
-                        .execute(&state.db)
-                        .await?;
-                    new_id
-                }
-            };
+    // Undo this revision and every one after it, newest first, to get back
+    // to the state the entry was in just before this revision was made
+    let revisions: Vec<EntryRevision> = sqlx::query_as(
+        "SELECT * FROM entry_revisions WHERE entry_id = ? AND created_at >= ? ORDER BY created_at DESC"
+    )
+    .bind(&id)
+    .bind(&revision.created_at)
+    .fetch_all(&state.db)
+    .await?;
+
+    let current = EntrySnapshot::new(&entry, fetch_entry_tags(&state.db, &id).await?);
+    let mut target = current.clone();
+    for revision in &revisions {
+        target.undo(&revision.field_changes());
+    }
 
-            sqlx::query("INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
-                .bind(&id)
-                .bind(&tag_id)
-                .execute(&state.db)
-                .await?;
+    // Going back to an older collection is a move like any other: the user
+    // must still be able to add to it, and to take the entry out of this one
+    if target.collection_id != current.collection_id {
+        let collections = fetch_user_collections(&state.db, &user.id).await;
+        let can_add = target
+            .collection_id
+            .as_ref()
+            .is_none_or(|cid| collections.iter().any(|c| &c.id == cid));
+        if !can_add || !can_move_out_of_collection(&state.db, &entry, &user.id).await? {
+            return Ok(Redirect::to(&format!("/entries/{}", id)));
         }
     }
 
-    Ok(Redirect::to("/").into_response())
+    write_snapshot(&state.db, &id, &target).await?;
+    record_revision(&state.db, &id, &user.id, &current, &target).await?;
+
+    Ok(Redirect::to(&format!("/entries/{}", id)))
 }
 
 async fn delete_entry(
@@ -716,23 +1656,25 @@ async fn delete_entry(
     AuthUser(user): AuthUser,
     Path(id): Path<String>,
 ) -> Result<impl IntoResponse, AppError> {
-    // Verify user owns this entry
-    let entry: Option<Entry> = sqlx::query_as(
-        "SELECT * FROM entries WHERE id = ? AND user_id = ?"
-    )
-    .bind(&id)
-    .bind(&user.id)
-    .fetch_optional(&state.db)
-    .await?;
+    // Verify user may edit this entry
+    let entry = fetch_editable_entry(&state.db, &id, &user.id).await?;
 
-    if entry.is_none() {
+    let Some(entry) = entry else {
         return Ok(([("HX-Redirect", "/")], "").into_response());
+    };
+
+    if let Some(event) = CollectionEvent::for_entry(EventKind::EntryRemoved, &user.id, &entry) {
+        event.record(&state.db).await?;
     }
 
+    let mut tx = state.db.begin().await?;
+    let tag_ids = fetch_entry_tag_ids(&mut *tx, &id).await?;
     sqlx::query("DELETE FROM entries WHERE id = ?")
         .bind(&id)
-        .execute(&state.db)
+        .execute(&mut *tx)
         .await?;
+    prune_unused_tags(&mut tx, &tag_ids).await?;
+    tx.commit().await?;
 
     // htmx expects empty response to remove element
     Ok(([("HX-Redirect", "/")], "").into_response())
@@ -756,6 +1698,8 @@ mod tests {
             dismissed_at,
             created_at: "2025-01-01T00:00:00+00:00".to_string(),
             updated_at: "2025-01-01T00:00:00+00:00".to_string(),
+            priority: Priority::Normal,
+            pinned: false,
         }
     }
 
@@ -971,9 +1915,49 @@ mod tests {
             interval: Interval::Days,
             tags: None,
             collection_id: None,
+            priority: Priority::Normal,
+            pinned: false,
+            schedule_set: false,
         }
     }
 
+    // --- parse_tags ---
+
+    #[test]
+    fn parse_tags_lowercases_and_dedupes() {
+        assert_eq!(parse_tags(Some(" Rust, web ,rust,,")), vec!["rust", "web"]);
+        assert!(parse_tags(None).is_empty());
+    }
+
+    fn scheduled_tag(name: &str, duration: i64, interval: Interval) -> Tag {
+        let mut tag = Tag::new(name.to_string(), &TagNamespace::User("user-1".to_string()));
+        tag.default_duration = Some(duration);
+        tag.default_interval = Some(interval);
+        tag
+    }
+
+    #[test]
+    fn tag_schedule_uses_first_scheduled_tag() {
+        let schedules = vec![scheduled_tag("news", 1, Interval::Days), scheduled_tag("reference", 1, Interval::Years)];
+        let tags = vec!["rust".to_string(), "reference".to_string(), "news".to_string()];
+        assert_eq!(tag_schedule(&schedules, &tags), Some((1, Interval::Years)));
+        assert_eq!(tag_schedule(&schedules, &["rust".to_string()]), None);
+    }
+
+    #[test]
+    fn tag_schedule_falls_back_to_ancestors() {
+        let schedules = vec![scheduled_tag("news", 1, Interval::Days), scheduled_tag("news/weekly", 1, Interval::Weeks)];
+        assert_eq!(tag_schedule(&schedules, &["news/tech/ai".to_string()]), Some((1, Interval::Days)));
+        assert_eq!(tag_schedule(&schedules, &["news/weekly/digest".to_string()]), Some((1, Interval::Weeks)));
+        assert_eq!(tag_schedule(&schedules, &["newsletter".to_string()]), None);
+    }
+
+    #[test]
+    fn parse_tags_tidies_hierarchy() {
+        assert_eq!(parse_tags(Some("Rust / Async/, /web//css")), vec!["rust/async", "web/css"]);
+        assert!(parse_tags(Some("/ /")).is_empty());
+    }
+
     #[test]
     fn entry_form_valid() {
         let form = make_valid_entry_form();
@@ -1,16 +1,21 @@
+use askama::Template;
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     http::{header, HeaderMap, HeaderValue},
-    response::IntoResponse,
+    response::{Html, IntoResponse, Redirect, Response},
     routing::get,
-    Json, Router,
+    Form, Json, Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::HashMap;
+use uuid::Uuid;
 
 use crate::auth::AuthUser;
 use crate::error::AppError;
-use crate::models::Interval;
+use crate::models::{Collection, CollectionRole, Interval, Priority, Tag, TagNamespace, User};
+use crate::routes::collections::insert_collection;
+use crate::routes::entries::{fetch_tag_schedules, normalize_url, parse_tags, MAX_NOTE_LEN};
 use crate::AppState;
 
 #[derive(FromRow)]
@@ -27,6 +32,8 @@ struct EntryWithTags {
     dismissed_at: Option<String>,
     created_at: String,
     updated_at: String,
+    priority: Priority,
+    pinned: bool,
     tags: Option<String>,
 }
 
@@ -41,7 +48,31 @@ struct ExportEntry {
     dismissed_at: Option<String>,
     created_at: String,
     updated_at: String,
+    priority: Priority,
+    pinned: bool,
     tags: Vec<String>,
+    notes: Vec<ExportNote>,
+    comments: Vec<ExportComment>,
+}
+
+#[derive(Serialize)]
+struct ExportNote {
+    visited_at: String,
+    note: String,
+}
+
+/// A comment on a collection entry. Deleted comments that still have
+/// replies are kept without a body so the thread stays intact.
+#[derive(Serialize, Deserialize, FromRow)]
+struct ExportComment {
+    #[serde(skip)]
+    entry_id: String,
+    id: String,
+    parent_id: Option<String>,
+    author: Option<String>,
+    body: Option<String>,
+    created_at: String,
+    updated_at: String,
 }
 
 #[derive(Serialize)]
@@ -50,8 +81,131 @@ struct ExportData {
     entries: Vec<ExportEntry>,
 }
 
+/// Marks a JSON file as a collection bundle
+const BUNDLE_FORMAT: &str = "interne-collection";
+/// Bump when the bundle layout changes incompatibly
+const BUNDLE_VERSION: u32 = 1;
+
+/// A whole collection as a self-contained JSON file, for `/collections/{id}/export`
+/// and `/collections/import`
+#[derive(Serialize, Deserialize)]
+pub struct CollectionBundle {
+    format: String,
+    version: u32,
+    exported_at: String,
+    collection: BundleCollection,
+    /// Tags with a default schedule of their own
+    #[serde(default)]
+    tags: Vec<BundleTag>,
+    #[serde(default)]
+    entries: Vec<BundleEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleCollection {
+    name: String,
+    #[serde(default)]
+    default_duration: Option<i64>,
+    #[serde(default)]
+    default_interval: Option<Interval>,
+    #[serde(default)]
+    default_tags: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleTag {
+    name: String,
+    default_duration: i64,
+    default_interval: Interval,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleEntry {
+    url: String,
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    duration: i64,
+    interval: Interval,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    pinned: bool,
+    created_at: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Only exported on request, with the visit history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dismissed_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    visits: Vec<BundleVisit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    comments: Vec<ExportComment>,
+}
+
+#[derive(Serialize, Deserialize, FromRow)]
+struct BundleVisit {
+    #[serde(skip)]
+    entry_id: String,
+    user: String,
+    visited_at: String,
+    note: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionExportQuery {
+    /// Include visits and notes: every member's for admins, otherwise the
+    /// caller's own
+    #[serde(default)]
+    visits: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CollectionImportForm {
+    /// Overrides the name in the bundle when set
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    bundle: String,
+}
+
+#[derive(Template)]
+#[template(path = "collections/import.html")]
+struct CollectionImportTemplate {
+    name: String,
+    bundle: String,
+    errors: HashMap<String, String>,
+    static_hash: &'static str,
+    user: Option<User>,
+}
+
 pub fn router() -> Router<AppState> {
-    Router::new().route("/export", get(export_data))
+    Router::new()
+        .route("/export", get(export_data))
+        .route("/collections/{id}/export", get(export_collection))
+        .route("/collections/import", get(import_collection_form).post(import_collection))
+}
+
+/// Attachment headers for a JSON download
+fn download_headers(filename: &str) -> HeaderMap {
+    let content_disposition = format!("attachment; filename=\"{}\"", filename);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&content_disposition).expect("filenames are built from ASCII only"),
+    );
+    headers
+}
+
+/// Group comments under the entries they belong to
+fn comments_by_entry(comments: Vec<ExportComment>) -> HashMap<String, Vec<ExportComment>> {
+    let mut by_entry: HashMap<String, Vec<ExportComment>> = HashMap::new();
+    for comment in comments {
+        by_entry.entry(comment.entry_id.clone()).or_default().push(comment);
+    }
+    by_entry
 }
 
 async fn export_data(
@@ -74,6 +228,48 @@ async fn export_data(
     .await
     .unwrap_or_default();
 
+    let notes: Vec<(String, String, String)> = sqlx::query_as(
+        r#"
+        SELECT v.entry_id, v.visited_at, v.note
+        FROM visits v
+        JOIN entries e ON e.id = v.entry_id
+        WHERE e.user_id = ? AND v.user_id = ? AND v.note IS NOT NULL
+        ORDER BY v.visited_at
+        "#,
+    )
+    .bind(&user.id)
+    .bind(&user.id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut notes_by_entry: HashMap<String, Vec<ExportNote>> = HashMap::new();
+    for (entry_id, visited_at, note) in notes {
+        notes_by_entry
+            .entry(entry_id)
+            .or_default()
+            .push(ExportNote { visited_at, note });
+    }
+
+    let comments: Vec<ExportComment> = sqlx::query_as(
+        r#"
+        SELECT c.entry_id, c.id, c.parent_id, u.name as author,
+            CASE WHEN c.deleted_at IS NULL THEN c.body END as body,
+            c.created_at, c.updated_at
+        FROM entry_comments c
+        JOIN entries e ON e.id = c.entry_id
+        LEFT JOIN users u ON u.id = c.user_id
+        WHERE e.user_id = ? AND e.collection_id IS NOT NULL
+        ORDER BY c.created_at
+        "#,
+    )
+    .bind(&user.id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let mut comments_by_entry = comments_by_entry(comments);
+
     let export_entries: Vec<ExportEntry> = rows
         .into_iter()
         .map(|row| {
@@ -82,6 +278,8 @@ async fn export_data(
                 .map(|t| t.split(',').map(|s| s.to_string()).collect())
                 .unwrap_or_default();
             ExportEntry {
+                notes: notes_by_entry.remove(&row.id).unwrap_or_default(),
+                comments: comments_by_entry.remove(&row.id).unwrap_or_default(),
                 id: row.id,
                 url: row.url,
                 title: row.title,
@@ -91,6 +289,8 @@ async fn export_data(
                 dismissed_at: row.dismissed_at,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
+                priority: row.priority,
+                pinned: row.pinned,
                 tags,
             }
         })
@@ -102,14 +302,407 @@ async fn export_data(
     };
 
     let filename = format!("interne-export-{}.json", chrono::Local::now().format("%Y-%m-%d"));
-    let content_disposition = format!("attachment; filename=\"{}\"", filename);
+    Ok((download_headers(&filename), Json(export)))
+}
 
-    let mut headers = HeaderMap::new();
-    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
-    headers.insert(
-        header::CONTENT_DISPOSITION,
-        HeaderValue::from_str(&content_disposition).expect("date format produces valid header chars"),
+async fn export_collection(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Query(query): Query<CollectionExportQuery>,
+) -> Result<Response, AppError> {
+    let Some(role) = CollectionRole::load(&state.db, &id, &user.id).await? else {
+        return Ok(Redirect::to("/collections").into_response());
+    };
+
+    let visitor = (!role.can_manage_members()).then_some(user.id.as_str());
+    let bundle = build_collection_bundle(&state.db, &id, query.visits, visitor).await?;
+    let filename = format!(
+        "interne-collection-{}-{}.json",
+        filename_slug(&bundle.collection.name),
+        chrono::Local::now().format("%Y-%m-%d")
     );
+    Ok((download_headers(&filename), Json(bundle)).into_response())
+}
+
+/// Lowercase ASCII letters and digits of a name, with dashes between words
+fn filename_slug(name: &str) -> String {
+    let slug = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_ascii_lowercase();
+    if slug.is_empty() { "collection".to_string() } else { slug }
+}
+
+/// Bundle a collection for export. With `include_visits`, the visit history
+/// is limited to `visitor`'s own visits when set.
+pub async fn build_collection_bundle(
+    db: &sqlx::SqlitePool,
+    collection_id: &str,
+    include_visits: bool,
+    visitor: Option<&str>,
+) -> Result<CollectionBundle, sqlx::Error> {
+    let collection: Collection = sqlx::query_as("SELECT * FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_one(db)
+        .await?;
+
+    let tags = fetch_tag_schedules(db, &TagNamespace::Collection(collection.id.clone()))
+        .await?
+        .into_iter()
+        .filter_map(|tag| {
+            Some(BundleTag {
+                default_duration: tag.default_duration?,
+                default_interval: tag.default_interval?,
+                name: tag.name,
+            })
+        })
+        .collect();
+
+    let rows: Vec<EntryWithTags> = sqlx::query_as(
+        r#"
+        SELECT e.*, GROUP_CONCAT(t.name) as tags
+        FROM entries e
+        LEFT JOIN entry_tags et ON et.entry_id = e.id
+        LEFT JOIN tags t ON t.id = et.tag_id
+        WHERE e.collection_id = ?
+        GROUP BY e.id
+        ORDER BY e.created_at
+        "#,
+    )
+    .bind(collection_id)
+    .fetch_all(db)
+    .await?;
+
+    let visits: Vec<BundleVisit> = if include_visits {
+        sqlx::query_as(
+            r#"
+            SELECT v.entry_id, u.name as user, v.visited_at, v.note
+            FROM visits v
+            JOIN entries e ON e.id = v.entry_id
+            JOIN users u ON u.id = v.user_id
+            WHERE e.collection_id = ? AND (? IS NULL OR v.user_id = ?)
+            ORDER BY v.visited_at
+            "#,
+        )
+        .bind(collection_id)
+        .bind(visitor)
+        .bind(visitor)
+        .fetch_all(db)
+        .await?
+    } else {
+        vec![]
+    };
+    let mut visits_by_entry: HashMap<String, Vec<BundleVisit>> = HashMap::new();
+    for visit in visits {
+        visits_by_entry.entry(visit.entry_id.clone()).or_default().push(visit);
+    }
+
+    let comments: Vec<ExportComment> = sqlx::query_as(
+        r#"
+        SELECT c.entry_id, c.id, c.parent_id, u.name as author,
+            CASE WHEN c.deleted_at IS NULL THEN c.body END as body,
+            c.created_at, c.updated_at
+        FROM entry_comments c
+        JOIN entries e ON e.id = c.entry_id
+        LEFT JOIN users u ON u.id = c.user_id
+        WHERE e.collection_id = ?
+        ORDER BY c.created_at
+        "#,
+    )
+    .bind(collection_id)
+    .fetch_all(db)
+    .await?;
+    let mut comments_by_entry = comments_by_entry(comments);
+
+    let entries = rows
+        .into_iter()
+        .map(|row| {
+            let mut tags: Vec<String> = row
+                .tags
+                .map(|t| t.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_default();
+            tags.sort();
+            BundleEntry {
+                visits: visits_by_entry.remove(&row.id).unwrap_or_default(),
+                comments: comments_by_entry.remove(&row.id).unwrap_or_default(),
+                dismissed_at: row.dismissed_at.filter(|_| include_visits),
+                url: row.url,
+                title: row.title,
+                description: row.description,
+                duration: row.duration,
+                interval: row.interval,
+                priority: row.priority,
+                pinned: row.pinned,
+                created_at: row.created_at,
+                tags,
+            }
+        })
+        .collect();
+
+    Ok(CollectionBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        collection: BundleCollection {
+            name: collection.name,
+            default_duration: collection.default_duration,
+            default_interval: collection.default_interval,
+            default_tags: collection.default_tags,
+        },
+        tags,
+        entries,
+    })
+}
+
+impl CollectionBundle {
+    /// Name for the imported collection: `name` if given, else the exported name
+    fn collection_name(&self, name: Option<String>) -> String {
+        name.or_else(|| Some(self.collection.name.trim().to_string()))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| "Imported collection".to_string())
+    }
+}
+
+/// Parse an uploaded bundle, or say why it can't be imported
+pub fn parse_collection_bundle(json: &str) -> Result<CollectionBundle, String> {
+    let bundle: CollectionBundle = serde_json::from_str(json).map_err(|e| format!("This isn't a valid collection export: {}", e))?;
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err("This isn't a collection export".to_string());
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err("This export is from a newer version of Interne".to_string());
+    }
+    for (i, entry) in bundle.entries.iter().enumerate() {
+        if let Err(e) = normalize_url(&entry.url) {
+            return Err(format!("Link {}: {}", i + 1, e));
+        }
+        if entry.title.trim().is_empty() {
+            return Err(format!("Link {}: Title is required", i + 1));
+        }
+        if entry.title.len() > 500 {
+            return Err(format!("Link {}: Title must be under 500 characters", i + 1));
+        }
+        if entry.description.as_ref().is_some_and(|d| d.len() > MAX_NOTE_LEN) {
+            return Err(format!("Link {}: Description must be under 5000 characters", i + 1));
+        }
+        if entry.duration < 1 {
+            return Err(format!("Link {}: Duration must be at least 1", i + 1));
+        }
+    }
+    Ok(bundle)
+}
+
+/// Create a new collection owned by `user_id` from a bundle, with its entries,
+/// tags and schedules. Entries start unread: visits and comments in the bundle
+/// belong to people on the exporting side and aren't imported.
+pub async fn import_collection_bundle(
+    db: &sqlx::SqlitePool,
+    user_id: &str,
+    bundle: CollectionBundle,
+    name: Option<String>,
+) -> Result<Collection, sqlx::Error> {
+    let mut collection = Collection::new(user_id.to_string(), bundle.collection_name(name));
+    collection.default_duration = bundle.collection.default_duration.filter(|d| *d >= 1);
+    collection.default_interval = collection.default_duration.map(|_| bundle.collection.default_interval.unwrap_or(Interval::Days));
+    collection.default_tags = parse_tags(Some(&bundle.collection.default_tags)).join(", ");
+
+    let namespace = TagNamespace::Collection(collection.id.clone());
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut tag_ids: HashMap<String, String> = HashMap::new();
+    let mut tx = db.begin().await?;
+
+    insert_collection(&mut tx, &collection).await?;
+
+    let mut tags: Vec<Tag> = Vec::new();
+    for bundle_tag in bundle.tags {
+        let Some(name) = parse_tags(Some(&bundle_tag.name)).pop() else {
+            continue;
+        };
+        let mut tag = Tag::new(name, &namespace);
+        tag.default_duration = Some(bundle_tag.default_duration).filter(|d| *d >= 1);
+        tag.default_interval = tag.default_duration.map(|_| bundle_tag.default_interval);
+        tags.push(tag);
+    }
+    for entry in &bundle.entries {
+        for name in parse_tags(Some(&entry.tags.join(","))) {
+            if !tags.iter().any(|t| t.name == name) {
+                tags.push(Tag::new(name, &namespace));
+            }
+        }
+    }
+    for tag in tags {
+        if tag_ids.contains_key(&tag.name) {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO tags (id, name, user_id, collection_id, created_at, default_duration, default_interval)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&tag.id)
+        .bind(&tag.name)
+        .bind(&tag.user_id)
+        .bind(&tag.collection_id)
+        .bind(&tag.created_at)
+        .bind(tag.default_duration)
+        .bind(tag.default_interval)
+        .execute(&mut *tx)
+        .await?;
+        tag_ids.insert(tag.name, tag.id);
+    }
+
+    for entry in bundle.entries {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO entries (id, user_id, collection_id, url, title, description, duration, interval, priority, pinned, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(&collection.id)
+        .bind(normalize_url(&entry.url).unwrap_or(entry.url))
+        .bind(entry.title.trim())
+        .bind(entry.description.filter(|d| !d.trim().is_empty()))
+        .bind(entry.duration)
+        .bind(entry.interval)
+        .bind(entry.priority)
+        .bind(entry.pinned)
+        .bind(&entry.created_at)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+
+        for name in parse_tags(Some(&entry.tags.join(","))) {
+            sqlx::query("INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) VALUES (?, ?)")
+                .bind(&id)
+                .bind(&tag_ids[&name])
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    tx.commit().await?;
+    Ok(collection)
+}
+
+async fn import_collection_form(AuthUser(user): AuthUser) -> Result<impl IntoResponse, AppError> {
+    let template = CollectionImportTemplate {
+        name: String::new(),
+        bundle: String::new(),
+        errors: HashMap::new(),
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn import_collection(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Form(form): Form<CollectionImportForm>,
+) -> Result<Response, AppError> {
+    let mut errors = HashMap::new();
+    let bundle = match parse_collection_bundle(&form.bundle) {
+        Ok(bundle) => Some(bundle),
+        Err(e) => {
+            errors.insert("bundle".to_string(), e);
+            None
+        }
+    };
+
+    // The collection is named after the bundle unless the form says otherwise
+    let name = Some(form.name.trim().to_string()).filter(|n| !n.is_empty());
+    let effective_name = bundle.as_ref().map(|b| b.collection_name(name.clone())).unwrap_or_default();
+    if effective_name.len() > 100 {
+        errors.insert("name".to_string(), "Name must be under 100 characters".to_string());
+    }
+
+    let (Some(bundle), true) = (bundle, errors.is_empty()) else {
+        let template = CollectionImportTemplate {
+            name: form.name,
+            bundle: form.bundle,
+            errors,
+            static_hash: crate::STATIC_HASH,
+            user: Some(user),
+        };
+        return Ok(Html(template.render()?).into_response());
+    };
+
+    let collection = import_collection_bundle(&state.db, &user.id, bundle, name).await?;
+    Ok(Redirect::to(&format!("/collections/{}", collection.id)).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle_json(entries: &str) -> String {
+        format!(
+            r#"{{"format": "interne-collection", "version": 1, "exported_at": "2024-01-01T00:00:00Z",
+                "collection": {{"name": "Team"}}, "entries": [{}]}}"#,
+            entries
+        )
+    }
+
+    #[test]
+    fn filename_slug_keeps_ascii_words() {
+        assert_eq!(filename_slug("Team Reading"), "team-reading");
+        assert_eq!(filename_slug("  Rust / Async!! "), "rust-async");
+        assert_eq!(filename_slug("Café"), "caf");
+        assert_eq!(filename_slug("日本"), "collection");
+    }
+
+    #[test]
+    fn parses_minimal_bundle() {
+        let json = bundle_json(r#"{"url": "example.com", "title": "A", "duration": 1, "interval": "days", "created_at": "2024-01-01T00:00:00Z"}"#);
+        let bundle = parse_collection_bundle(&json).unwrap();
+        assert_eq!(bundle.entries.len(), 1);
+        assert_eq!(bundle.entries[0].priority, Priority::Normal);
+        assert_eq!(bundle.collection_name(None), "Team");
+        assert_eq!(bundle.collection_name(Some("Other".to_string())), "Other");
+    }
+
+    #[test]
+    fn rejects_other_formats_and_newer_versions() {
+        let json = bundle_json("").replace("interne-collection", "something-else");
+        assert_eq!(parse_collection_bundle(&json).err().unwrap(), "This isn't a collection export");
+        let json = bundle_json("").replace(r#""version": 1"#, r#""version": 2"#);
+        assert!(parse_collection_bundle(&json).err().unwrap().contains("newer version"));
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let json = bundle_json(r#"{"url": "example.com", "title": " ", "duration": 1, "interval": "days", "created_at": "2024-01-01T00:00:00Z"}"#);
+        assert_eq!(parse_collection_bundle(&json).err().unwrap(), "Link 1: Title is required");
+        let json = bundle_json(r#"{"url": "example.com", "title": "A", "duration": 0, "interval": "days", "created_at": "2024-01-01T00:00:00Z"}"#);
+        assert_eq!(parse_collection_bundle(&json).err().unwrap(), "Link 1: Duration must be at least 1");
+        let json = bundle_json(r#"{"url": "example.com", "title": "A", "duration": 1, "interval": "fortnights", "created_at": "2024-01-01T00:00:00Z"}"#);
+        assert!(parse_collection_bundle(&json).is_err());
+    }
 
-    Ok((headers, Json(export)))
+    #[test]
+    fn rejects_entries_over_the_form_limits() {
+        let entry = |title: &str, description: &str| {
+            bundle_json(&format!(
+                r#"{{"url": "example.com", "title": "{}", "description": "{}", "duration": 1, "interval": "days", "created_at": "2024-01-01T00:00:00Z"}}"#,
+                title, description
+            ))
+        };
+        assert!(parse_collection_bundle(&entry(&"a".repeat(500), &"a".repeat(5000))).is_ok());
+        assert_eq!(
+            parse_collection_bundle(&entry(&"a".repeat(501), "")).err().unwrap(),
+            "Link 1: Title must be under 500 characters"
+        );
+        assert_eq!(
+            parse_collection_bundle(&entry("A", &"a".repeat(5001))).err().unwrap(),
+            "Link 1: Description must be under 5000 characters"
+        );
+    }
 }
@@ -1,5 +1,8 @@
 pub mod auth;
 pub mod collections;
+pub mod comments;
 pub mod entries;
 pub mod export;
+pub mod settings;
+pub mod shared;
 pub mod tags;
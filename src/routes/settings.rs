@@ -0,0 +1,165 @@
+use askama::Template;
+use axum::{
+    extract::{Query, State},
+    response::{Html, IntoResponse, Redirect},
+    routing::{get, post},
+    Form, Router,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::auth::AuthUser;
+use crate::error::AppError;
+use crate::models::{Collection, Interval, User, UserSettings};
+use crate::routes::entries::{fetch_user_collections, parse_tags};
+use crate::AppState;
+
+#[derive(Template)]
+#[template(path = "settings.html")]
+struct SettingsTemplate {
+    settings: UserSettings,
+    collections: Vec<Collection>,
+    errors: HashMap<String, String>,
+    saved: bool,
+    static_hash: &'static str,
+    user: Option<User>,
+}
+
+#[derive(Deserialize)]
+pub struct SettingsQuery {
+    saved: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SettingsForm {
+    default_duration: i64,
+    default_interval: Interval,
+    default_collection_id: Option<String>,
+    default_tags: Option<String>,
+}
+
+fn validate_settings_form(form: &SettingsForm, collections: &[Collection]) -> HashMap<String, String> {
+    let mut errors = HashMap::new();
+
+    if form.default_duration < 1 {
+        errors.insert("default_duration".to_string(), "Duration must be at least 1".to_string());
+    }
+
+    let collection_id = form.default_collection_id.as_deref().filter(|s| !s.is_empty());
+    if collection_id.is_some_and(|id| !collections.iter().any(|c| c.id == id)) {
+        errors.insert("default_collection_id".to_string(), "Choose one of your collections".to_string());
+    }
+
+    errors
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/settings", get(settings_page))
+        .route("/settings", post(update_settings))
+}
+
+async fn settings_page(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<SettingsQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let settings = UserSettings::load(&state.db, &user.id).await?;
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+
+    let template = SettingsTemplate {
+        settings,
+        collections,
+        errors: HashMap::new(),
+        saved: query.saved.is_some(),
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn update_settings(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Form(form): Form<SettingsForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let collections = fetch_user_collections(&state.db, &user.id).await;
+
+    let settings = UserSettings {
+        user_id: user.id.clone(),
+        default_duration: form.default_duration,
+        default_interval: form.default_interval,
+        default_collection_id: form.default_collection_id.clone().filter(|s| !s.is_empty()),
+        default_tags: parse_tags(form.default_tags.as_deref()).join(", "),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let errors = validate_settings_form(&form, &collections);
+    if !errors.is_empty() {
+        let template = SettingsTemplate {
+            settings,
+            collections,
+            errors,
+            saved: false,
+            static_hash: crate::STATIC_HASH,
+            user: Some(user),
+        };
+        return Ok(Html(template.render()?).into_response());
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO user_settings (user_id, default_duration, default_interval, default_collection_id, default_tags, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(user_id) DO UPDATE SET
+            default_duration = excluded.default_duration,
+            default_interval = excluded.default_interval,
+            default_collection_id = excluded.default_collection_id,
+            default_tags = excluded.default_tags,
+            updated_at = excluded.updated_at
+        "#
+    )
+    .bind(&settings.user_id)
+    .bind(settings.default_duration)
+    .bind(settings.default_interval)
+    .bind(&settings.default_collection_id)
+    .bind(&settings.default_tags)
+    .bind(&settings.updated_at)
+    .execute(&state.db)
+    .await?;
+
+    Ok(Redirect::to("/settings?saved=1").into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_form() -> SettingsForm {
+        SettingsForm {
+            default_duration: 2,
+            default_interval: Interval::Weeks,
+            default_collection_id: None,
+            default_tags: Some("reading".to_string()),
+        }
+    }
+
+    #[test]
+    fn settings_form_valid() {
+        assert!(validate_settings_form(&make_form(), &[]).is_empty());
+    }
+
+    #[test]
+    fn settings_form_duration_zero() {
+        let mut form = make_form();
+        form.default_duration = 0;
+        assert!(validate_settings_form(&form, &[]).contains_key("default_duration"));
+    }
+
+    #[test]
+    fn settings_form_unknown_collection() {
+        let mut form = make_form();
+        form.default_collection_id = Some("not-mine".to_string());
+        assert!(validate_settings_form(&form, &[]).contains_key("default_collection_id"));
+    }
+}
@@ -0,0 +1,150 @@
+//! Public, read-only views of collections that have a share link. These
+//! routes need no login and never record visits.
+
+use askama::Template;
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{Html, IntoResponse},
+    routing::get,
+    Router,
+};
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::markdown;
+use crate::models::{Collection, Entry, User};
+use crate::AppState;
+
+#[derive(Template)]
+#[template(path = "shared/collection.html")]
+struct SharedCollectionTemplate {
+    collection: Collection,
+    token: String,
+    entries: Vec<SharedEntry>,
+    static_hash: &'static str,
+    /// Always `None`; the page is for visitors without an account
+    user: Option<User>,
+}
+
+#[derive(Template)]
+#[template(path = "shared/feed.xml")]
+struct SharedFeedTemplate {
+    collection: Collection,
+    token: String,
+    /// Most recent change to the collection or any of its entries
+    updated: String,
+    entries: Vec<SharedEntry>,
+}
+
+struct SharedEntry {
+    id: String,
+    url: String,
+    title: String,
+    description_html: Option<String>,
+    tags: Vec<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/shared/{token}", get(shared_collection))
+        .route("/shared/{token}/feed.atom", get(shared_feed))
+}
+
+async fn fetch_shared_collection(db: &sqlx::SqlitePool, token: &str) -> Result<Option<Collection>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM collections WHERE share_token = ?")
+        .bind(token)
+        .fetch_optional(db)
+        .await
+}
+
+/// The collection's entries, newest first, with their tags
+async fn fetch_shared_entries(db: &sqlx::SqlitePool, collection_id: &str) -> Result<Vec<SharedEntry>, sqlx::Error> {
+    let entries: Vec<Entry> = sqlx::query_as(
+        "SELECT * FROM entries WHERE collection_id = ? ORDER BY pinned DESC, created_at DESC"
+    )
+    .bind(collection_id)
+    .fetch_all(db)
+    .await?;
+
+    let tag_rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT et.entry_id, t.name
+        FROM entry_tags et
+        JOIN tags t ON t.id = et.tag_id
+        JOIN entries e ON e.id = et.entry_id
+        WHERE e.collection_id = ?
+        ORDER BY t.name
+        "#
+    )
+    .bind(collection_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for (entry_id, name) in tag_rows {
+        tags.entry(entry_id).or_default().push(name);
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| SharedEntry {
+            description_html: markdown::render_optional(entry.description.as_deref()),
+            tags: tags.remove(&entry.id).unwrap_or_default(),
+            id: entry.id,
+            url: entry.url,
+            title: entry.title,
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+        })
+        .collect())
+}
+
+async fn shared_collection(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(collection) = fetch_shared_collection(&state.db, &token).await? else {
+        return Err(AppError::NotFound);
+    };
+
+    let entries = fetch_shared_entries(&state.db, &collection.id).await?;
+
+    let template = SharedCollectionTemplate {
+        collection,
+        token,
+        entries,
+        static_hash: crate::STATIC_HASH,
+        user: None,
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn shared_feed(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(collection) = fetch_shared_collection(&state.db, &token).await? else {
+        return Err(AppError::NotFound);
+    };
+
+    let entries = fetch_shared_entries(&state.db, &collection.id).await?;
+    // Timestamps are all RFC 3339 in UTC, so the largest string is the latest
+    let updated = entries
+        .iter()
+        .map(|e| e.updated_at.as_str())
+        .chain([collection.updated_at.as_str()])
+        .max()
+        .unwrap_or_default()
+        .to_string();
+
+    let template = SharedFeedTemplate {
+        collection,
+        token,
+        updated,
+        entries,
+    };
+    Ok(([(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")], template.render()?))
+}
@@ -1,16 +1,23 @@
 use askama::Template;
 use axum::{
-    extract::{Path, State},
-    response::{Html, IntoResponse},
-    routing::get,
-    Router,
+    extract::{Path, Query, State},
+    response::{Html, IntoResponse, Redirect},
+    routing::{delete, get, post},
+    Form, Router,
 };
 use chrono::Utc;
+use serde::Deserialize;
+use sqlx::FromRow;
+use std::collections::{BTreeMap, HashSet};
 
 use crate::auth::AuthUser;
 use crate::error::AppError;
-use crate::models::User;
-use crate::routes::entries::{build_entry_view, EntryView, EntryWithCount};
+use crate::models::{Interval, Tag, User};
+use crate::routes::comments::fill_comment_counts;
+use crate::routes::entries::{
+    build_entry_view, format_last_viewed, matches_view_filter, parse_tags, EntryView,
+    EntryWithCount,
+};
 use crate::AppState;
 
 struct TagWithCount {
@@ -18,6 +25,91 @@ struct TagWithCount {
     count: i64,
 }
 
+/// Two tags used on the same entries, and how many entries that is
+#[derive(Debug, PartialEq)]
+struct TagPair {
+    a: String,
+    b: String,
+    count: i64,
+}
+
+/// A tag placed on the co-occurrence graph
+struct GraphNode {
+    name: String,
+    x: String,
+    y: String,
+    label_x: String,
+    label_y: String,
+    /// `start` or `end`, so labels sit outside the circle
+    anchor: &'static str,
+}
+
+struct GraphEdge {
+    x1: String,
+    y1: String,
+    x2: String,
+    y2: String,
+    width: String,
+}
+
+struct Breadcrumb {
+    name: String,
+    path: String,
+}
+
+/// How a tag's entries are doing, for the tag list
+#[derive(Debug, Default, PartialEq)]
+struct TagStats {
+    ready: i64,
+    waiting: i64,
+    unseen: i64,
+    visits: i64,
+    /// RFC3339 time of the most recent visit to any of the entries
+    last_visited_at: Option<String>,
+}
+
+impl TagStats {
+    fn count(&self) -> i64 {
+        self.ready + self.waiting
+    }
+}
+
+struct TagStatsView {
+    name: String,
+    stats: TagStats,
+    last_reviewed: Option<String>,
+}
+
+/// An entry under one of its tags
+#[derive(FromRow)]
+struct TaggedEntryRow {
+    tag_name: String,
+    last_visited_at: Option<String>,
+    #[sqlx(flatten)]
+    entry: EntryWithCount,
+}
+
+/// The parts of a tagged entry that go into [`TagStats`]
+struct TaggedEntry {
+    tag_name: String,
+    entry_id: String,
+    is_available: bool,
+    visit_count: i64,
+    last_visited_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TagShowQuery {
+    /// `ready`, `waiting`, `unseen` or `all` (the default)
+    filter: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TagListQuery {
+    /// Collapse `rust/async` and `rust/web` into `rust`
+    top: Option<String>,
+}
+
 struct TagCloudItem {
     name: String,
     count: i64,
@@ -29,6 +121,8 @@ struct TagCloudItem {
 #[template(path = "tags/list.html")]
 struct TagListTemplate {
     tags: Vec<TagCloudItem>,
+    stats: Vec<TagStatsView>,
+    top_level: bool,
     static_hash: &'static str,
     user: Option<User>,
 }
@@ -37,12 +131,108 @@ struct TagListTemplate {
 #[template(path = "tags/show.html")]
 struct TagShowTemplate {
     tag_name: String,
+    /// Ancestors of a hierarchical tag, outermost first
+    breadcrumbs: Vec<Breadcrumb>,
+    /// Last segment of the tag name
+    leaf_name: String,
+    children: Vec<TagWithCount>,
+    /// Tags most often on the same entries as this one
+    related: Vec<TagWithCount>,
+    /// Default schedules of the tags with this name the user can manage
+    schedules: Vec<TagScheduleRow>,
+    error: Option<String>,
+    filter: String,
     entry_count: usize,
     entries: Vec<EntryView>,
     static_hash: &'static str,
     user: Option<User>,
 }
 
+#[derive(Template)]
+#[template(path = "tags/graph.html")]
+struct TagGraphTemplate {
+    pairs: Vec<TagPair>,
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    static_hash: &'static str,
+    user: Option<User>,
+}
+
+/// Tag suggestions shown under the entry form's tags field
+#[derive(Template)]
+#[template(path = "tags/suggestions.html")]
+struct TagSuggestionsTemplate {
+    label: &'static str,
+    /// Whether a suggestion replaces the term being typed or adds a tag
+    replace_last: bool,
+    suggestions: Vec<TagWithCount>,
+}
+
+#[derive(Deserialize)]
+pub struct SuggestQuery {
+    /// The tags field as typed so far
+    tags: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "tags/manage.html")]
+struct TagManageTemplate {
+    groups: Vec<TagGroup>,
+    error: Option<String>,
+    static_hash: &'static str,
+    user: Option<User>,
+}
+
+/// A tag the user can manage, with how many entries use it
+#[derive(FromRow)]
+struct ManagedTagRow {
+    id: String,
+    name: String,
+    collection_id: Option<String>,
+    collection_name: Option<String>,
+    count: i64,
+}
+
+/// Manageable tags from one namespace: the user's private tags, or one of
+/// the collections they own
+struct TagGroup {
+    label: String,
+    tags: Vec<ManagedTagRow>,
+}
+
+/// One namespace's tag with a given name, and its default schedule
+#[derive(FromRow)]
+struct TagScheduleRow {
+    id: String,
+    collection_name: Option<String>,
+    default_duration: Option<i64>,
+    default_interval: Option<Interval>,
+}
+
+impl TagScheduleRow {
+    fn label(&self) -> &str {
+        self.collection_name.as_deref().unwrap_or("Private")
+    }
+
+    /// Interval shown in the form; days if the tag has no schedule yet
+    fn interval(&self) -> String {
+        self.default_interval.unwrap_or(Interval::Days).to_string()
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TagScheduleForm {
+    /// Blank clears the schedule
+    default_duration: String,
+    default_interval: Interval,
+}
+
+#[derive(Deserialize)]
+pub struct RenameTagForm {
+    name: String,
+}
+
 fn build_tag_cloud(tags: Vec<TagWithCount>) -> Vec<TagCloudItem> {
     if tags.is_empty() {
         return vec![];
@@ -95,41 +285,290 @@ fn build_tag_cloud(tags: Vec<TagWithCount>) -> Vec<TagCloudItem> {
         .collect()
 }
 
+/// Everything but tag pages lives outside `/tags/`, so any tag name, even
+/// `graph` or `manage`, still gets its own page
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/tags", get(list_tags))
-        .route("/tags/{name}", get(show_tag))
+        .route("/tag-graph", get(tag_graph))
+        .route("/tags-api/autocomplete", get(autocomplete_tags))
+        .route("/tags-api/suggest", get(suggest_tags_for_url))
+        .route("/tag-manager", get(manage_tags))
+        .route("/tag-manager/merge", post(merge_selected_tags))
+        .route("/tag-manager/cleanup", post(cleanup_tags))
+        .route("/tag-manager/{id}/rename", post(rename_tag))
+        .route("/tag-manager/{id}/schedule", post(set_tag_schedule))
+        .route("/tag-manager/{id}", delete(delete_tag))
+        .route("/tags/{*name}", get(show_tag))
 }
 
-async fn list_tags(
-    State(state): State<AppState>,
-    AuthUser(user): AuthUser,
-) -> Result<impl IntoResponse, AppError> {
-    let tags: Vec<(String, i64)> = sqlx::query_as(
+/// The page for a tag, with each segment of a hierarchical name encoded
+fn tag_path(name: &str) -> String {
+    let mut url = url::Url::parse("http://localhost/tags").unwrap();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.extend(name.split('/'));
+    }
+    url.path().to_string()
+}
+
+/// `rust` for `rust/async`
+fn top_level_name(name: &str) -> &str {
+    name.split('/').next().unwrap_or(name)
+}
+
+/// The child of `parent` that `name` sits under, e.g. `rust/async` for
+/// `rust/async/tokio` within `rust`. `None` if `name` isn't below `parent`.
+fn child_name(name: &str, parent: &str) -> Option<String> {
+    let rest = name.strip_prefix(parent)?.strip_prefix('/')?;
+    let segment = rest.split('/').next()?;
+    Some(format!("{}/{}", parent, segment))
+}
+
+/// Count distinct entries per group of tag names, sorted by group name.
+/// `group` maps a tag name to the name it's counted under, or `None` to skip it,
+/// so an entry tagged both `rust/async` and `rust/web` counts once for `rust`.
+fn count_tags<F>(links: &[(String, String)], group: F) -> Vec<TagWithCount>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut entries: BTreeMap<String, HashSet<&str>> = BTreeMap::new();
+    for (name, entry_id) in links {
+        if let Some(key) = group(name) {
+            entries.entry(key).or_default().insert(entry_id);
+        }
+    }
+    entries
+        .into_iter()
+        .map(|(name, ids)| TagWithCount { name, count: ids.len() as i64 })
+        .collect()
+}
+
+/// How often each pair of tag names shares an entry, most frequent first
+fn co_occurrences(links: &[(String, String)]) -> Vec<TagPair> {
+    let mut tags_by_entry: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (name, entry_id) in links {
+        let tags = tags_by_entry.entry(entry_id).or_default();
+        if !tags.contains(&name.as_str()) {
+            tags.push(name);
+        }
+    }
+
+    let mut counts: BTreeMap<(&str, &str), i64> = BTreeMap::new();
+    for tags in tags_by_entry.values_mut() {
+        tags.sort();
+        for (i, a) in tags.iter().enumerate() {
+            for b in &tags[i + 1..] {
+                *counts.entry((a, b)).or_default() += 1;
+            }
+        }
+    }
+
+    let mut pairs: Vec<TagPair> = counts
+        .into_iter()
+        .map(|((a, b), count)| TagPair { a: a.to_string(), b: b.to_string(), count })
+        .collect();
+    pairs.sort_by_key(|pair| std::cmp::Reverse(pair.count));
+    pairs
+}
+
+/// The tags paired with `name`, most frequent first
+fn related_tags(pairs: &[TagPair], name: &str) -> Vec<TagWithCount> {
+    pairs
+        .iter()
+        .filter_map(|pair| {
+            let other = if pair.a == name {
+                &pair.b
+            } else if pair.b == name {
+                &pair.a
+            } else {
+                return None;
+            };
+            Some(TagWithCount { name: other.clone(), count: pair.count })
+        })
+        .collect()
+}
+
+/// Lay the most connected tags out on a circle in a 400x400 box, with an
+/// edge for each pair between them, thicker for pairs seen more often
+fn layout_graph(pairs: &[TagPair], max_nodes: usize) -> (Vec<GraphNode>, Vec<GraphEdge>) {
+    let mut degree: BTreeMap<&str, i64> = BTreeMap::new();
+    for pair in pairs {
+        *degree.entry(&pair.a).or_default() += pair.count;
+        *degree.entry(&pair.b).or_default() += pair.count;
+    }
+    let mut names: Vec<(&str, i64)> = degree.into_iter().collect();
+    names.sort_by(|x, y| y.1.cmp(&x.1).then(x.0.cmp(y.0)));
+    names.truncate(max_nodes);
+
+    let (cx, cy, radius) = (200.0_f64, 200.0_f64, 140.0_f64);
+    let positions: BTreeMap<&str, (f64, f64, f64)> = names
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| {
+            let angle = i as f64 / names.len() as f64 * std::f64::consts::TAU - std::f64::consts::FRAC_PI_2;
+            (*name, (cx + radius * angle.cos(), cy + radius * angle.sin(), angle))
+        })
+        .collect();
+
+    let nodes = names
+        .iter()
+        .map(|(name, _)| {
+            let (x, y, angle) = positions[name];
+            GraphNode {
+                name: name.to_string(),
+                x: format!("{:.1}", x),
+                y: format!("{:.1}", y),
+                label_x: format!("{:.1}", x + 8.0 * angle.cos()),
+                label_y: format!("{:.1}", y + 8.0 * angle.sin() + 4.0),
+                anchor: if angle.cos() < -0.01 { "end" } else { "start" },
+            }
+        })
+        .collect();
+
+    let max_count = pairs.iter().map(|p| p.count).max().unwrap_or(1) as f64;
+    let edges = pairs
+        .iter()
+        .filter_map(|pair| {
+            let (x1, y1, _) = positions.get(pair.a.as_str())?;
+            let (x2, y2, _) = positions.get(pair.b.as_str())?;
+            Some(GraphEdge {
+                x1: format!("{:.1}", x1),
+                y1: format!("{:.1}", y1),
+                x2: format!("{:.1}", x2),
+                y2: format!("{:.1}", y2),
+                width: format!("{:.1}", 1.0 + 4.0 * pair.count as f64 / max_count),
+            })
+        })
+        .collect();
+
+    (nodes, edges)
+}
+
+/// Stats per group of tag names, counting each entry once per group like
+/// [`count_tags`]
+fn collect_tag_stats<F>(tagged: &[TaggedEntry], group: F) -> BTreeMap<String, TagStats>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut stats: BTreeMap<String, TagStats> = BTreeMap::new();
+    let mut counted: HashSet<(String, &str)> = HashSet::new();
+    for tagged in tagged {
+        let Some(key) = group(&tagged.tag_name) else {
+            continue;
+        };
+        if !counted.insert((key.clone(), &tagged.entry_id)) {
+            continue;
+        }
+
+        let s = stats.entry(key).or_default();
+        if tagged.is_available {
+            s.ready += 1;
+        } else {
+            s.waiting += 1;
+        }
+        if tagged.visit_count == 0 {
+            s.unseen += 1;
+        }
+        s.visits += tagged.visit_count;
+        if tagged.last_visited_at > s.last_visited_at {
+            s.last_visited_at = tagged.last_visited_at.clone();
+        }
+    }
+    stats
+}
+
+/// Every tagged entry in the user's namespaces, with its availability now
+async fn fetch_visible_tagged_entries(db: &sqlx::SqlitePool, user_id: &str) -> Result<Vec<TaggedEntry>, sqlx::Error> {
+    let rows: Vec<TaggedEntryRow> = sqlx::query_as(
         r#"
-        SELECT t.name, COUNT(et.entry_id) as count
+        SELECT t.name as tag_name, e.*, COUNT(v.id) as visit_count, MAX(v.visited_at) as last_visited_at
         FROM tags t
         JOIN entry_tags et ON et.tag_id = t.id
         JOIN entries e ON e.id = et.entry_id
-        WHERE e.user_id = ?
-        GROUP BY t.id
-        ORDER BY t.name ASC
+        LEFT JOIN visits v ON v.entry_id = e.id
+        WHERE t.user_id = ? OR t.collection_id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ?
+        )
+        GROUP BY t.id, e.id
         "#
     )
-    .bind(&user.id)
-    .fetch_all(&state.db)
-    .await
-    .unwrap_or_default();
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
 
-    let tag_counts: Vec<TagWithCount> = tags
+    let now = Utc::now();
+    Ok(rows
         .into_iter()
-        .map(|(name, count)| TagWithCount { name, count })
-        .collect();
+        .map(|row| {
+            let (entry, visit_count) = row.entry.into_entry_and_count();
+            let entry_id = entry.id.clone();
+            let view = build_entry_view(entry, visit_count, now);
+            TaggedEntry {
+                tag_name: row.tag_name,
+                entry_id,
+                is_available: view.is_available,
+                visit_count,
+                last_visited_at: row.last_visited_at,
+            }
+        })
+        .collect())
+}
+
+/// (tag name, entry id) for every tagged entry in the user's namespaces: their
+/// own tags plus those of collections they own or belong to
+async fn fetch_visible_tag_links(db: &sqlx::SqlitePool, user_id: &str) -> Vec<(String, String)> {
+    sqlx::query_as(
+        r#"
+        SELECT t.name, et.entry_id
+        FROM tags t
+        JOIN entry_tags et ON et.tag_id = t.id
+        WHERE t.user_id = ? OR t.collection_id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ?
+        )
+        "#
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+    .unwrap_or_default()
+}
+
+async fn list_tags(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<TagListQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let top_level = query.top.is_some();
+    let tagged = fetch_visible_tagged_entries(&state.db, &user.id).await?;
 
+    let tag_stats = if top_level {
+        collect_tag_stats(&tagged, |name| Some(top_level_name(name).to_string()))
+    } else {
+        collect_tag_stats(&tagged, |name| Some(name.to_string()))
+    };
+
+    let tag_counts = tag_stats
+        .iter()
+        .map(|(name, stats)| TagWithCount { name: name.clone(), count: stats.count() })
+        .collect();
     let cloud_items = build_tag_cloud(tag_counts);
 
+    let now = Utc::now();
+    let stats = tag_stats
+        .into_iter()
+        .map(|(name, stats)| TagStatsView {
+            last_reviewed: format_last_viewed(&stats.last_visited_at, now),
+            name,
+            stats,
+        })
+        .collect();
+
     let template = TagListTemplate {
         tags: cloud_items,
+        stats,
+        top_level,
         static_hash: crate::STATIC_HASH,
         user: Some(user),
     };
@@ -140,7 +579,19 @@ async fn show_tag(
     State(state): State<AppState>,
     AuthUser(user): AuthUser,
     Path(name): Path<String>,
+    Query(query): Query<TagShowQuery>,
 ) -> Result<impl IntoResponse, AppError> {
+    let filter = query.filter.unwrap_or_else(|| "all".to_string());
+    render_tag_page(&state.db, user, name, &filter, None).await
+}
+
+async fn render_tag_page(
+    db: &sqlx::SqlitePool,
+    user: User,
+    name: String,
+    filter: &str,
+    error: Option<String>,
+) -> Result<Html<String>, AppError> {
     let rows: Vec<EntryWithCount> = sqlx::query_as(
         r#"
         SELECT e.*, COUNT(v.id) as visit_count
@@ -148,30 +599,74 @@ async fn show_tag(
         JOIN entry_tags et ON et.entry_id = e.id
         JOIN tags t ON t.id = et.tag_id
         LEFT JOIN visits v ON v.entry_id = e.id
-        WHERE t.name = ? AND e.user_id = ?
+        WHERE (t.name = ? OR substr(t.name, 1, length(?) + 1) = ? || '/')
+        AND (t.user_id = ? OR t.collection_id IN (
+            SELECT collection_id FROM collection_access WHERE user_id = ?
+        ))
         GROUP BY e.id
-        ORDER BY e.dismissed_at DESC NULLS FIRST
+        ORDER BY e.pinned DESC, e.dismissed_at DESC NULLS FIRST
         "#
     )
     .bind(&name)
+    .bind(&name)
+    .bind(&name)
+    .bind(&user.id)
     .bind(&user.id)
-    .fetch_all(&state.db)
+    .fetch_all(db)
     .await
     .unwrap_or_default();
 
     let now = Utc::now();
-    let entries: Vec<EntryView> = rows
+    let mut entries: Vec<EntryView> = rows
         .into_iter()
         .map(|r| {
             let (entry, count) = r.into_entry_and_count();
             build_entry_view(entry, count, now)
         })
+        .filter(|ev| matches_view_filter(ev, filter))
         .collect();
+    fill_comment_counts(db, &user.id, &mut entries).await;
 
     let entry_count = entries.len();
 
+    let links = fetch_visible_tag_links(db, &user.id).await;
+    let children = count_tags(&links, |tag| child_name(tag, &name));
+    let mut related = related_tags(&co_occurrences(&links), &name);
+    related.truncate(8);
+
+    let segments: Vec<&str> = name.split('/').collect();
+    let breadcrumbs = (1..segments.len())
+        .map(|depth| Breadcrumb {
+            name: segments[depth - 1].to_string(),
+            path: segments[..depth].join("/"),
+        })
+        .collect();
+    let leaf_name = segments.last().copied().unwrap_or_default().to_string();
+
+    let schedules: Vec<TagScheduleRow> = sqlx::query_as(
+        r#"
+        SELECT t.id, c.name as collection_name, t.default_duration, t.default_interval
+        FROM tags t
+        LEFT JOIN collections c ON c.id = t.collection_id
+        WHERE t.name = ? AND (t.user_id = ? OR c.owner_id = ?)
+        ORDER BY t.collection_id IS NOT NULL, c.name
+        "#
+    )
+    .bind(&name)
+    .bind(&user.id)
+    .bind(&user.id)
+    .fetch_all(db)
+    .await?;
+
     let template = TagShowTemplate {
         tag_name: name,
+        breadcrumbs,
+        leaf_name,
+        children,
+        related,
+        schedules,
+        error,
+        filter: filter.to_string(),
         entry_count,
         entries,
         static_hash: crate::STATIC_HASH,
@@ -179,3 +674,607 @@ async fn show_tag(
     };
     Ok(Html(template.render()?))
 }
+
+/// Edit distance between two strings, counting a swap of adjacent
+/// characters as one edit so `rsut` is one away from `rust`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Existing tags for a partly typed `term`, best first: tags starting with
+/// it, then tags containing it, then likely typos of it. Within each group
+/// the most used tags come first.
+fn rank_completions(term: &str, tags: Vec<TagWithCount>, exclude: &[String]) -> Vec<TagWithCount> {
+    let term_len = term.chars().count();
+    let max_typos = if term_len < 3 { 0 } else if term_len < 6 { 1 } else { 2 };
+
+    let mut ranked: Vec<(usize, TagWithCount)> = tags
+        .into_iter()
+        .filter(|tag| !exclude.contains(&tag.name))
+        .filter_map(|tag| {
+            let group = if tag.name.starts_with(term) {
+                0
+            } else if tag.name.contains(term) {
+                1
+            } else {
+                // Compare against the whole name and against as much of it
+                // as has been typed
+                let typed: String = tag.name.chars().take(term_len).collect();
+                let distance = edit_distance(term, &tag.name).min(edit_distance(term, &typed));
+                if distance > max_typos {
+                    return None;
+                }
+                2
+            };
+            Some((group, tag))
+        })
+        .collect();
+
+    ranked.sort_by(|(ga, a), (gb, b)| ga.cmp(gb).then(b.count.cmp(&a.count)).then(a.name.cmp(&b.name)));
+    ranked.into_iter().map(|(_, tag)| tag).take(8).collect()
+}
+
+/// The host of a URL as typed in the entry form, without `www.`
+fn url_host(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let with_scheme = if raw.contains("://") { raw.to_string() } else { format!("https://{}", raw) };
+    let url = url::Url::parse(&with_scheme).ok()?;
+    let host = url.host_str()?;
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+/// Suggest existing tags as the user types in the tags field
+async fn autocomplete_tags(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<SuggestQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let input = query.tags.unwrap_or_default();
+    let (entered, term) = match input.rsplit_once(',') {
+        Some((entered, term)) => (parse_tags(Some(entered)), term),
+        None => (Vec::new(), input.as_str()),
+    };
+    let term = parse_tags(Some(term)).pop().unwrap_or_default();
+
+    let suggestions = if term.is_empty() {
+        Vec::new()
+    } else {
+        let links = fetch_visible_tag_links(&state.db, &user.id).await;
+        rank_completions(&term, count_tags(&links, |name| Some(name.to_string())), &entered)
+    };
+
+    let template = TagSuggestionsTemplate {
+        label: "Existing tags",
+        replace_last: true,
+        suggestions,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Suggest tags used on the user's other entries from the same site as `url`
+async fn suggest_tags_for_url(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Query(query): Query<SuggestQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let entered = parse_tags(query.tags.as_deref());
+    let host = query.url.as_deref().and_then(url_host);
+
+    let mut suggestions = Vec::new();
+    if let Some(host) = host {
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT e.url, t.name, e.id
+            FROM entries e
+            JOIN entry_tags et ON et.entry_id = e.id
+            JOIN tags t ON t.id = et.tag_id
+            WHERE e.user_id = ? OR e.collection_id IN (
+                SELECT collection_id FROM collection_access WHERE user_id = ?
+            )
+            "#
+        )
+        .bind(&user.id)
+        .bind(&user.id)
+        .fetch_all(&state.db)
+        .await?;
+
+        let links: Vec<(String, String)> = rows
+            .into_iter()
+            .filter(|(url, _, _)| url_host(url).as_deref() == Some(host.as_str()))
+            .map(|(_, name, entry_id)| (name, entry_id))
+            .collect();
+
+        suggestions = count_tags(&links, |name| Some(name.to_string()))
+            .into_iter()
+            .filter(|tag| !entered.contains(&tag.name))
+            .collect();
+        suggestions.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(&b.name)));
+        suggestions.truncate(5);
+    }
+
+    let template = TagSuggestionsTemplate {
+        label: "Used on this site",
+        replace_last: false,
+        suggestions,
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Which tags turn up on the same entries, as a graph and a list of pairs
+async fn tag_graph(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    let links = fetch_visible_tag_links(&state.db, &user.id).await;
+    let mut pairs = co_occurrences(&links);
+    let (nodes, edges) = layout_graph(&pairs, 20);
+    pairs.truncate(50);
+
+    let template = TagGraphTemplate {
+        pairs,
+        nodes,
+        edges,
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?))
+}
+
+/// Normalize a submitted tag name the same way the entry form does. Returns
+/// `None` unless it is exactly one non-empty tag.
+fn normalize_tag_name(raw: &str) -> Option<String> {
+    let mut tags = parse_tags(Some(raw));
+    if tags.len() == 1 { tags.pop() } else { None }
+}
+
+/// A tag the user may manage: one of their private tags, or a tag of a
+/// collection they own
+async fn fetch_manageable_tag(db: &sqlx::SqlitePool, tag_id: &str, user_id: &str) -> Result<Option<Tag>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT t.* FROM tags t
+        LEFT JOIN collections c ON c.id = t.collection_id
+        WHERE t.id = ? AND (t.user_id = ? OR c.owner_id = ?)
+        "#
+    )
+    .bind(tag_id)
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// Give every source tag the name `name`. The result is a single tag in the
+/// sources' namespace: an existing tag with that name if there is one,
+/// otherwise the first source renamed. Entries keep every tag they had.
+async fn merge_tags(db: &sqlx::SqlitePool, sources: &[Tag], name: &str) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+    merge_tags_in(&mut tx, sources, name).await?;
+    tx.commit().await
+}
+
+/// `merge_tags` within a transaction the caller commits
+async fn merge_tags_in(tx: &mut sqlx::SqliteConnection, sources: &[Tag], name: &str) -> Result<(), sqlx::Error> {
+    let Some(first) = sources.first() else {
+        return Ok(());
+    };
+    let namespace = first.namespace();
+
+    let existing: Option<(String,)> = sqlx::query_as(
+        "SELECT id FROM tags WHERE name = ? AND user_id IS ? AND collection_id IS ?"
+    )
+    .bind(name)
+    .bind(namespace.user_id())
+    .bind(namespace.collection_id())
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let target_id = match existing {
+        Some((id,)) => id,
+        None => {
+            sqlx::query("UPDATE tags SET name = ? WHERE id = ?")
+                .bind(name)
+                .bind(&first.id)
+                .execute(&mut *tx)
+                .await?;
+            first.id.clone()
+        }
+    };
+
+    for source in sources.iter().filter(|t| t.id != target_id) {
+        sqlx::query(
+            "INSERT OR IGNORE INTO entry_tags (entry_id, tag_id) SELECT entry_id, ? FROM entry_tags WHERE tag_id = ?"
+        )
+        .bind(&target_id)
+        .bind(&source.id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Cascades to the source's entry_tags
+        sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(&source.id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn render_manage_page(db: &sqlx::SqlitePool, user: User, error: Option<String>) -> Result<Html<String>, AppError> {
+    let rows: Vec<ManagedTagRow> = sqlx::query_as(
+        r#"
+        SELECT t.id, t.name, t.collection_id, c.name as collection_name, COUNT(et.entry_id) as count
+        FROM tags t
+        LEFT JOIN collections c ON c.id = t.collection_id
+        LEFT JOIN entry_tags et ON et.tag_id = t.id
+        WHERE t.user_id = ? OR c.owner_id = ?
+        GROUP BY t.id
+        ORDER BY t.collection_id IS NOT NULL, c.name, t.collection_id, t.name
+        "#
+    )
+    .bind(&user.id)
+    .bind(&user.id)
+    .fetch_all(db)
+    .await?;
+
+    let mut groups: Vec<TagGroup> = Vec::new();
+    let mut current: Option<Option<String>> = None;
+    for row in rows {
+        if current.as_ref() != Some(&row.collection_id) {
+            current = Some(row.collection_id.clone());
+            groups.push(TagGroup {
+                label: row.collection_name.clone().unwrap_or_else(|| "Private".to_string()),
+                tags: Vec::new(),
+            });
+        }
+        if let Some(group) = groups.last_mut() {
+            group.tags.push(row);
+        }
+    }
+
+    let template = TagManageTemplate {
+        groups,
+        error,
+        static_hash: crate::STATIC_HASH,
+        user: Some(user),
+    };
+    Ok(Html(template.render()?))
+}
+
+async fn manage_tags(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    render_manage_page(&state.db, user, None).await
+}
+
+/// Rename a tag. Renaming to a name that already exists in the same
+/// namespace merges the two.
+async fn rename_tag(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<RenameTagForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(tag) = fetch_manageable_tag(&state.db, &id, &user.id).await? else {
+        return Ok(Redirect::to("/tag-manager").into_response());
+    };
+
+    let Some(name) = normalize_tag_name(&form.name) else {
+        let error = Some("Enter a single tag name".to_string());
+        return Ok(render_manage_page(&state.db, user, error).await?.into_response());
+    };
+    if name.starts_with(&format!("{}/", tag.name)) {
+        let error = Some("A tag can't move under itself".to_string());
+        return Ok(render_manage_page(&state.db, user, error).await?.into_response());
+    }
+
+    // Children move with their parent: `lang/rust` becomes `languages/rust`
+    let namespace = tag.namespace();
+    let mut tx = state.db.begin().await?;
+    let children: Vec<Tag> = sqlx::query_as(
+        r#"
+        SELECT * FROM tags
+        WHERE substr(name, 1, length(?) + 1) = ? || '/' AND user_id IS ? AND collection_id IS ?
+        "#
+    )
+    .bind(&tag.name)
+    .bind(&tag.name)
+    .bind(namespace.user_id())
+    .bind(namespace.collection_id())
+    .fetch_all(&mut *tx)
+    .await?;
+
+    for child in children {
+        let child_name = format!("{}{}", name, &child.name[tag.name.len()..]);
+        merge_tags_in(&mut tx, &[child], &child_name).await?;
+    }
+    merge_tags_in(&mut tx, &[tag], &name).await?;
+    tx.commit().await?;
+
+    Ok(Redirect::to("/tag-manager").into_response())
+}
+
+/// Merge the checked tags into one. The form repeats `tag_id` for each
+/// checked tag, which `Form` can't collect, so the body is parsed directly.
+async fn merge_selected_tags(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    body: String,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tag_ids = Vec::new();
+    let mut name = String::new();
+    for (key, value) in url::form_urlencoded::parse(body.as_bytes()) {
+        match key.as_ref() {
+            "tag_id" => tag_ids.push(value.into_owned()),
+            "name" => name = value.into_owned(),
+            _ => {}
+        }
+    }
+
+    let mut sources = Vec::new();
+    for id in &tag_ids {
+        if let Some(tag) = fetch_manageable_tag(&state.db, id, &user.id).await? {
+            sources.push(tag);
+        }
+    }
+
+    let error = if sources.len() < 2 {
+        Some("Select at least two tags to merge".to_string())
+    } else if sources.iter().any(|t| t.namespace() != sources[0].namespace()) {
+        Some("Only tags from the same collection can be merged".to_string())
+    } else {
+        None
+    };
+    if error.is_some() {
+        return Ok(render_manage_page(&state.db, user, error).await?.into_response());
+    }
+
+    let Some(name) = normalize_tag_name(&name) else {
+        let error = Some("Enter a single tag name".to_string());
+        return Ok(render_manage_page(&state.db, user, error).await?.into_response());
+    };
+
+    merge_tags(&state.db, &sources, &name).await?;
+
+    Ok(Redirect::to("/tag-manager").into_response())
+}
+
+/// Remove a tag from every entry that has it
+async fn delete_tag(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if fetch_manageable_tag(&state.db, &id, &user.id).await?.is_some() {
+        sqlx::query("DELETE FROM tags WHERE id = ?")
+            .bind(&id)
+            .execute(&state.db)
+            .await?;
+    }
+
+    Ok(([("HX-Redirect", "/tag-manager")], "").into_response())
+}
+
+/// Set or clear the default schedule new entries get from a tag
+async fn set_tag_schedule(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+    Path(id): Path<String>,
+    Form(form): Form<TagScheduleForm>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(tag) = fetch_manageable_tag(&state.db, &id, &user.id).await? else {
+        return Ok(Redirect::to("/tag-manager").into_response());
+    };
+
+    let duration = form.default_duration.trim();
+    let schedule = if duration.is_empty() {
+        None
+    } else {
+        match duration.parse::<i64>() {
+            Ok(duration) if duration >= 1 => Some((duration, form.default_interval)),
+            _ => {
+                let error = Some("Duration must be at least 1".to_string());
+                return Ok(render_tag_page(&state.db, user, tag.name, "all", error).await?.into_response());
+            }
+        }
+    };
+
+    sqlx::query("UPDATE tags SET default_duration = ?, default_interval = ? WHERE id = ?")
+        .bind(schedule.map(|(duration, _)| duration))
+        .bind(schedule.map(|(_, interval)| interval))
+        .bind(&tag.id)
+        .execute(&state.db)
+        .await?;
+
+    Ok(Redirect::to(&tag_path(&tag.name)).into_response())
+}
+
+/// Drop unused tags from the namespaces the user manages, keeping those
+/// with a default schedule
+async fn cleanup_tags(
+    State(state): State<AppState>,
+    AuthUser(user): AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    sqlx::query(
+        r#"
+        DELETE FROM tags
+        WHERE (user_id = ? OR collection_id IN (SELECT id FROM collections WHERE owner_id = ?))
+        AND default_duration IS NULL
+        AND NOT EXISTS (SELECT 1 FROM entry_tags WHERE tag_id = tags.id)
+        "#
+    )
+    .bind(&user.id)
+    .bind(&user.id)
+    .execute(&state.db)
+    .await?;
+    Ok(Redirect::to("/tag-manager"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(t, e)| (t.to_string(), e.to_string())).collect()
+    }
+
+    fn counts(pairs: &[(&str, i64)]) -> Vec<TagWithCount> {
+        pairs.iter().map(|(name, count)| TagWithCount { name: name.to_string(), count: *count }).collect()
+    }
+
+    fn names(tags: &[TagWithCount]) -> Vec<&str> {
+        tags.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    #[test]
+    fn edit_distance_counts_transpositions_once() {
+        assert_eq!(edit_distance("rsut", "rust"), 1);
+        assert_eq!(edit_distance("rust", "rust"), 0);
+        assert_eq!(edit_distance("go", "rust"), 4);
+    }
+
+    #[test]
+    fn completions_rank_prefix_then_contains_then_typos() {
+        let tags = counts(&[("rustacean", 1), ("rust", 5), ("trust", 9), ("rest", 2), ("go", 7)]);
+        let ranked = rank_completions("rus", tags, &[]);
+        assert_eq!(names(&ranked), vec!["rust", "rustacean", "trust", "rest"]);
+
+        let ranked = rank_completions("rsut", counts(&[("rust", 5), ("go", 7)]), &[]);
+        assert_eq!(names(&ranked), vec!["rust"]);
+    }
+
+    #[test]
+    fn completions_skip_entered_tags() {
+        let ranked = rank_completions("r", counts(&[("rust", 5), ("react", 1)]), &["rust".to_string()]);
+        assert_eq!(names(&ranked), vec!["react"]);
+    }
+
+    #[test]
+    fn url_host_ignores_scheme_and_www() {
+        assert_eq!(url_host("https://www.example.com/a").as_deref(), Some("example.com"));
+        assert_eq!(url_host("example.com/b").as_deref(), Some("example.com"));
+        assert_eq!(url_host("  "), None);
+    }
+
+    fn tagged(tag_name: &str, entry_id: &str, is_available: bool, visit_count: i64, last: Option<&str>) -> TaggedEntry {
+        TaggedEntry {
+            tag_name: tag_name.to_string(),
+            entry_id: entry_id.to_string(),
+            is_available,
+            visit_count,
+            last_visited_at: last.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn tag_stats_break_down_availability() {
+        let tagged = vec![
+            tagged("rust", "e1", true, 0, None),
+            tagged("rust", "e2", false, 3, Some("2024-01-02T00:00:00+00:00")),
+            tagged("rust", "e3", true, 1, Some("2024-03-01T00:00:00+00:00")),
+            tagged("go", "e4", false, 1, Some("2023-01-01T00:00:00+00:00")),
+        ];
+        let stats = collect_tag_stats(&tagged, |name| Some(name.to_string()));
+        assert_eq!(
+            stats["rust"],
+            TagStats {
+                ready: 2,
+                waiting: 1,
+                unseen: 1,
+                visits: 4,
+                last_visited_at: Some("2024-03-01T00:00:00+00:00".to_string()),
+            }
+        );
+        assert_eq!(stats["go"].count(), 1);
+    }
+
+    #[test]
+    fn collapsed_tag_stats_count_each_entry_once() {
+        let tagged = vec![tagged("rust/async", "e1", true, 2, None), tagged("rust/web", "e1", true, 2, None)];
+        let stats = collect_tag_stats(&tagged, |name| Some(top_level_name(name).to_string()));
+        assert_eq!(stats["rust"].ready, 1);
+        assert_eq!(stats["rust"].visits, 2);
+    }
+
+    #[test]
+    fn co_occurrences_count_shared_entries() {
+        let links = links(&[("rust", "e1"), ("web", "e1"), ("rust", "e2"), ("web", "e2"), ("go", "e2"), ("go", "e3")]);
+        let pairs = co_occurrences(&links);
+        assert_eq!(pairs[0], TagPair { a: "rust".to_string(), b: "web".to_string(), count: 2 });
+        assert_eq!(pairs.len(), 3);
+
+        let related = related_tags(&pairs, "web");
+        let related: Vec<(&str, i64)> = related.iter().map(|t| (t.name.as_str(), t.count)).collect();
+        assert_eq!(related, vec![("rust", 2), ("go", 1)]);
+    }
+
+    #[test]
+    fn graph_keeps_most_connected_tags() {
+        let pairs = vec![
+            TagPair { a: "rust".to_string(), b: "web".to_string(), count: 4 },
+            TagPair { a: "go".to_string(), b: "rust".to_string(), count: 1 },
+        ];
+        let (nodes, edges) = layout_graph(&pairs, 2);
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["rust", "web"]);
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].width, "5.0");
+        // First node sits at the top of the circle
+        assert_eq!((nodes[0].x.as_str(), nodes[0].y.as_str()), ("200.0", "60.0"));
+    }
+
+    #[test]
+    fn tag_path_encodes_each_segment() {
+        assert_eq!(tag_path("rust/async"), "/tags/rust/async");
+        assert_eq!(tag_path("machine learning"), "/tags/machine%20learning");
+    }
+
+    #[test]
+    fn top_level_name_takes_first_segment() {
+        assert_eq!(top_level_name("rust/async/tokio"), "rust");
+        assert_eq!(top_level_name("rust"), "rust");
+    }
+
+    #[test]
+    fn child_name_only_matches_descendants() {
+        assert_eq!(child_name("rust/async/tokio", "rust").as_deref(), Some("rust/async"));
+        assert_eq!(child_name("rust/web", "rust").as_deref(), Some("rust/web"));
+        assert_eq!(child_name("rust", "rust"), None);
+        assert_eq!(child_name("rustacean/web", "rust"), None);
+    }
+
+    #[test]
+    fn collapsed_counts_each_entry_once() {
+        let links = links(&[("rust/async", "e1"), ("rust/web", "e1"), ("rust", "e2"), ("go", "e3")]);
+        let counts = count_tags(&links, |name| Some(top_level_name(name).to_string()));
+        let counts: Vec<(&str, i64)> = counts.iter().map(|t| (t.name.as_str(), t.count)).collect();
+        assert_eq!(counts, vec![("go", 1), ("rust", 2)]);
+    }
+
+    #[test]
+    fn children_counts_group_by_next_segment() {
+        let links = links(&[("rust/async/tokio", "e1"), ("rust/async", "e2"), ("rust/web", "e3"), ("rust", "e4")]);
+        let counts = count_tags(&links, |name| child_name(name, "rust"));
+        let counts: Vec<(&str, i64)> = counts.iter().map(|t| (t.name.as_str(), t.count)).collect();
+        assert_eq!(counts, vec![("rust/async", 2), ("rust/web", 1)]);
+    }
+}
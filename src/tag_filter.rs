@@ -0,0 +1,203 @@
+/// How a tag term in a filter combines with the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagOp {
+    /// Bare term: the entry needs at least one of these
+    Any,
+    /// `+term`: the entry needs every one of these
+    All,
+    /// `-term`: the entry must have none of these
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagTerm {
+    pub op: TagOp,
+    pub name: String,
+}
+
+impl TagTerm {
+    /// The term as written in a filter, e.g. `+rust`
+    pub fn label(&self) -> String {
+        let prefix = match self.op {
+            TagOp::Any => "",
+            TagOp::All => "+",
+            TagOp::None => "-",
+        };
+        format!("{}{}", prefix, self.name)
+    }
+
+    /// A tag matches a term with the same name, or one of its descendants
+    /// in the tag hierarchy (`rust` matches `rust/async`).
+    fn matches(&self, tag: &str) -> bool {
+        tag == self.name
+            || tag
+                .strip_prefix(self.name.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// A boolean tag filter such as `rust, +web, -archived`: entries must have
+/// `web`, must not have `archived`, and must have at least one of the bare
+/// terms (`rust`) if there are any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    pub terms: Vec<TagTerm>,
+}
+
+impl TagFilter {
+    pub fn parse(raw: &str) -> Self {
+        let mut terms: Vec<TagTerm> = Vec::new();
+        for part in raw.split(',') {
+            let part = part.trim();
+            let (op, name) = if let Some(rest) = part.strip_prefix('+') {
+                (TagOp::All, rest)
+            } else if let Some(rest) = part.strip_prefix('-') {
+                (TagOp::None, rest)
+            } else {
+                (TagOp::Any, part)
+            };
+
+            // Same normalization as tags saved from the entry form
+            let Some(name) = crate::routes::entries::parse_tags(Some(name)).pop() else {
+                continue;
+            };
+
+            let term = TagTerm { op, name };
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        Self { terms }
+    }
+
+    /// Read the `tags` parameter from a raw query string. A literal `+` at the
+    /// start of a term is the AND prefix, so `?tags=rust,+web` means "+web"
+    /// rather than " web" as form decoding would have it. Browsers encode `+`
+    /// and `,` typed into a form, so form submissions decode as usual.
+    pub fn from_query(raw_query: Option<&str>) -> Self {
+        let Some(raw_query) = raw_query else {
+            return Self::default();
+        };
+
+        for pair in raw_query.split('&') {
+            let Some(value) = pair.strip_prefix("tags=") else {
+                continue;
+            };
+
+            let mut escaped = String::with_capacity(value.len());
+            let mut term_start = true;
+            for c in value.chars() {
+                if c == '+' && term_start {
+                    escaped.push_str("%2B");
+                } else {
+                    escaped.push(c);
+                }
+                term_start = c == ',';
+            }
+
+            let decoded: String = url::form_urlencoded::parse(format!("v={}", escaped).as_bytes())
+                .map(|(_, v)| v.into_owned())
+                .next()
+                .unwrap_or_default();
+            return Self::parse(&decoded);
+        }
+
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, tags: &[String]) -> bool {
+        let has = |term: &TagTerm| tags.iter().any(|tag| term.matches(tag));
+
+        let mut any_terms = self.terms.iter().filter(|t| t.op == TagOp::Any).peekable();
+        let any_ok = any_terms.peek().is_none() || any_terms.any(has);
+
+        any_ok
+            && self.terms.iter().filter(|t| t.op == TagOp::All).all(has)
+            && !self.terms.iter().filter(|t| t.op == TagOp::None).any(has)
+    }
+
+    /// The filter as typed into the search form, e.g. `rust, +web`
+    pub fn to_input_value(&self) -> String {
+        self.terms.iter().map(|t| t.label()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// The filter without one of its terms
+    pub fn without(&self, index: usize) -> Self {
+        let mut terms = self.terms.clone();
+        terms.remove(index);
+        Self { terms }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_prefixes() {
+        let filter = TagFilter::parse("Rust, +web , -archived,,");
+        assert_eq!(
+            filter.terms,
+            vec![
+                TagTerm { op: TagOp::Any, name: "rust".to_string() },
+                TagTerm { op: TagOp::All, name: "web".to_string() },
+                TagTerm { op: TagOp::None, name: "archived".to_string() },
+            ]
+        );
+        assert_eq!(filter.to_input_value(), "rust, +web, -archived");
+    }
+
+    #[test]
+    fn from_query_keeps_literal_plus_prefix() {
+        let filter = TagFilter::from_query(Some("q=&tags=rust,+web,-archived"));
+        assert_eq!(filter.to_input_value(), "rust, +web, -archived");
+    }
+
+    #[test]
+    fn from_query_decodes_form_submissions() {
+        // "rust, machine learning, +web" typed into the search form
+        let filter = TagFilter::from_query(Some("tags=rust%2C+machine+learning%2C+%2Bweb"));
+        assert_eq!(filter.to_input_value(), "rust, machine learning, +web");
+        assert!(TagFilter::from_query(None).is_empty());
+        assert!(TagFilter::from_query(Some("q=rust")).is_empty());
+    }
+
+    #[test]
+    fn any_terms_are_ored() {
+        let filter = TagFilter::parse("rust, go");
+        assert!(filter.matches(&tags(&["go"])));
+        assert!(!filter.matches(&tags(&["python"])));
+    }
+
+    #[test]
+    fn all_and_none_terms() {
+        let filter = TagFilter::parse("+rust, +web, -archived");
+        assert!(filter.matches(&tags(&["rust", "web"])));
+        assert!(!filter.matches(&tags(&["rust"])));
+        assert!(!filter.matches(&tags(&["rust", "web", "archived"])));
+
+        let filter = TagFilter::parse("-archived");
+        assert!(filter.matches(&[]));
+    }
+
+    #[test]
+    fn terms_match_descendant_tags() {
+        let filter = TagFilter::parse("+rust");
+        assert!(filter.matches(&tags(&["rust/async"])));
+        assert!(!filter.matches(&tags(&["rustacean"])));
+    }
+
+    #[test]
+    fn without_removes_one_term() {
+        let filter = TagFilter::parse("rust, +web");
+        assert_eq!(filter.without(0).to_input_value(), "+web");
+    }
+}
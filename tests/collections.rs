@@ -56,6 +56,7 @@ async fn show_collection_as_owner() {
     .execute(&app.db)
     .await
     .unwrap();
+    app.add_invite(&col_id, "invite-123").await;
 
     let resp = app
         .get(&format!("/collections/{}", col_id), Some(&cookie))
@@ -282,6 +283,7 @@ async fn join_collection_via_invite_code() {
     .execute(&app.db)
     .await
     .unwrap();
+    app.add_invite(&col_id, "join-code-123").await;
 
     let resp = app
         .post_form(
@@ -302,43 +304,6 @@ async fn join_collection_via_invite_code() {
     assert_eq!(count.0, 1);
 }
 
-#[tokio::test]
-async fn regenerate_invite_as_owner() {
-    let app = TestApp::new().await;
-    let (user_id, invite_code) = app.create_user("Owner").await;
-    let cookie = app.login(&invite_code).await;
-
-    let col_id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
-    sqlx::query(
-        "INSERT INTO collections (id, owner_id, name, invite_code, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
-    )
-    .bind(&col_id)
-    .bind(&user_id)
-    .bind("Regen Test")
-    .bind("old-code")
-    .bind(&now)
-    .bind(&now)
-    .execute(&app.db)
-    .await
-    .unwrap();
-
-    app.post_form(
-        &format!("/collections/{}/regenerate-invite", col_id),
-        "",
-        Some(&cookie),
-    )
-    .await;
-
-    let (new_code,): (String,) =
-        sqlx::query_as("SELECT invite_code FROM collections WHERE id = ?")
-            .bind(&col_id)
-            .fetch_one(&app.db)
-            .await
-            .unwrap();
-    assert_ne!(new_code, "old-code");
-}
-
 #[tokio::test]
 async fn owner_removes_member() {
     let app = TestApp::new().await;
@@ -437,3 +402,1083 @@ async fn member_leaves_collection() {
             .unwrap();
     assert_eq!(count.0, 0);
 }
+
+// --- Roles ---
+
+async fn member_role(app: &TestApp, collection_id: &str, user_id: &str) -> Option<String> {
+    sqlx::query_as("SELECT role FROM collection_members WHERE collection_id = ? AND user_id = ?")
+        .bind(collection_id)
+        .bind(user_id)
+        .fetch_optional(&app.db)
+        .await
+        .unwrap()
+        .map(|(role,): (String,)| role)
+}
+
+async fn entry_id(app: &TestApp, title: &str) -> String {
+    let (id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = ?")
+        .bind(title)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    id
+}
+
+#[tokio::test]
+async fn members_join_as_editors() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    let (code,): (String,) = sqlx::query_as("SELECT invite_code FROM collections WHERE id = ?")
+        .bind(&col_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form("/collections/join", &format!("invite_code={}", code), Some(&cookie)).await;
+
+    assert_eq!(member_role(&app, &col_id, &member_id).await.as_deref(), Some("editor"));
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&cookie)).await).await;
+    assert!(html.contains("Your role: Editor"));
+}
+
+#[tokio::test]
+async fn viewer_can_visit_but_not_add_or_edit() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (viewer_id, viewer_invite) = app.create_user("Viewer").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let viewer_cookie = app.login(&viewer_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &viewer_id).await;
+    app.set_role(&col_id, &viewer_id, "viewer").await;
+
+    let body = format!("url=example.com&title=Shared&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let id = entry_id(&app, "Shared").await;
+
+    // Viewers can't add links to the collection
+    let body = format!("url=example.com&title=Sneaky&duration=1&interval=days&tags=&collection_id={}", col_id);
+    let resp = app.post_form("/entries", &body, Some(&viewer_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("add links to this collection"));
+    let html = body_string(app.get("/entries/new", Some(&viewer_cookie)).await).await;
+    assert!(!html.contains(&col_id));
+
+    // ...or edit or delete its entries
+    let resp = app.get(&format!("/entries/{}/edit", id), Some(&viewer_cookie)).await;
+    assert_redirect(&resp, "/");
+    let body = format!("url=example.com&title=Changed&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form(&format!("/entries/{}", id), &body, Some(&viewer_cookie)).await;
+    app.delete(&format!("/entries/{}", id), Some(&viewer_cookie)).await;
+    let (title,): (String,) = sqlx::query_as("SELECT title FROM entries WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(title, "Shared");
+
+    // ...but can read and visit them
+    let resp = app.post_form(&format!("/entries/{}/visit", id), "", Some(&viewer_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn editor_can_edit_other_members_entries() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (editor_id, editor_invite) = app.create_user("Editor").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let editor_cookie = app.login(&editor_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &editor_id).await;
+
+    let body = format!("url=example.com&title=Shared&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let id = entry_id(&app, "Shared").await;
+
+    let resp = app.get(&format!("/entries/{}/edit", id), Some(&editor_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body = format!("url=example.com&title=Edited&duration=1&interval=days&tags=&collection_id={}", col_id);
+    let resp = app.post_form(&format!("/entries/{}", id), &body, Some(&editor_cookie)).await;
+    assert_redirect(&resp, "/");
+    let (title,): (String,) = sqlx::query_as("SELECT title FROM entries WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(title, "Edited");
+}
+
+#[tokio::test]
+async fn admins_manage_members_but_editors_cannot() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (admin_id, admin_invite) = app.create_user("Admin").await;
+    let (editor_id, editor_invite) = app.create_user("Editor").await;
+    let admin_cookie = app.login(&admin_invite).await;
+    let editor_cookie = app.login(&editor_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &admin_id).await;
+    app.add_member(&col_id, &editor_id).await;
+    app.set_role(&col_id, &admin_id, "admin").await;
+
+    // Editors can't change roles or remove members
+    app.post_form(&format!("/collections/{}/members/{}/role", col_id, admin_id), "role=viewer", Some(&editor_cookie))
+        .await;
+    assert_eq!(member_role(&app, &col_id, &admin_id).await.as_deref(), Some("admin"));
+    app.delete(&format!("/collections/{}/members/{}", col_id, admin_id), Some(&editor_cookie)).await;
+    assert!(member_role(&app, &col_id, &admin_id).await.is_some());
+
+    // Admins can, but can't hand out ownership
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&admin_cookie)).await).await;
+    assert!(html.contains("Create Invite"));
+    let resp = app
+        .post_form(&format!("/collections/{}/members/{}/role", col_id, editor_id), "role=viewer", Some(&admin_cookie))
+        .await;
+    assert_redirect(&resp, &format!("/collections/{}", col_id));
+    assert_eq!(member_role(&app, &col_id, &editor_id).await.as_deref(), Some("viewer"));
+    app.post_form(&format!("/collections/{}/members/{}/role", col_id, editor_id), "role=owner", Some(&admin_cookie))
+        .await;
+    assert_eq!(member_role(&app, &col_id, &editor_id).await.as_deref(), Some("viewer"));
+
+    app.delete(&format!("/collections/{}/members/{}", col_id, editor_id), Some(&admin_cookie)).await;
+    assert!(member_role(&app, &col_id, &editor_id).await.is_none());
+}
+
+#[tokio::test]
+async fn only_the_owner_manages_admins() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (admin_id, admin_invite) = app.create_user("Admin").await;
+    let (other_id, _) = app.create_user("Other Admin").await;
+    let (editor_id, _) = app.create_user("Editor").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let admin_cookie = app.login(&admin_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    for id in [&admin_id, &other_id, &editor_id] {
+        app.add_member(&col_id, id).await;
+    }
+    app.set_role(&col_id, &admin_id, "admin").await;
+    app.set_role(&col_id, &other_id, "admin").await;
+
+    // An admin can't demote or remove another admin, or make someone an admin
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&admin_cookie)).await).await;
+    assert!(!html.contains(&format!("/members/{}/role", other_id)));
+    assert!(html.contains(&format!("/members/{}/role", editor_id)));
+    app.post_form(&format!("/collections/{}/members/{}/role", col_id, other_id), "role=viewer", Some(&admin_cookie))
+        .await;
+    assert_eq!(member_role(&app, &col_id, &other_id).await.as_deref(), Some("admin"));
+    app.delete(&format!("/collections/{}/members/{}", col_id, other_id), Some(&admin_cookie)).await;
+    assert!(member_role(&app, &col_id, &other_id).await.is_some());
+    app.post_form(&format!("/collections/{}/members/{}/role", col_id, editor_id), "role=admin", Some(&admin_cookie))
+        .await;
+    assert_eq!(member_role(&app, &col_id, &editor_id).await.as_deref(), Some("editor"));
+    let resp = app
+        .post_form(&format!("/collections/{}/invites", col_id), "label=&role=admin&max_uses=&expires_in_days=", Some(&admin_cookie))
+        .await;
+    assert!(body_string(resp).await.contains("Only the owner can invite admins"));
+    let (admin_invites,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM collection_invites WHERE role = 'admin'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(admin_invites, 0);
+
+    // The owner can
+    app.post_form(&format!("/collections/{}/members/{}/role", col_id, other_id), "role=viewer", Some(&owner_cookie))
+        .await;
+    assert_eq!(member_role(&app, &col_id, &other_id).await.as_deref(), Some("viewer"));
+    app.delete(&format!("/collections/{}/members/{}", col_id, admin_id), Some(&owner_cookie)).await;
+    assert!(member_role(&app, &col_id, &admin_id).await.is_none());
+}
+
+async fn entry_collection(app: &TestApp, id: &str) -> Option<String> {
+    let (collection_id,): (Option<String>,) = sqlx::query_as("SELECT collection_id FROM entries WHERE id = ?")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    collection_id
+}
+
+#[tokio::test]
+async fn only_the_author_or_an_admin_moves_entries_out() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (author_id, author_invite) = app.create_user("Author").await;
+    let (editor_id, editor_invite) = app.create_user("Editor").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let author_cookie = app.login(&author_invite).await;
+    let editor_cookie = app.login(&editor_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &author_id).await;
+    app.add_member(&col_id, &editor_id).await;
+
+    for title in ["Kept", "Taken"] {
+        let body = format!("url=example.com&title={}&duration=1&interval=days&tags=&collection_id={}", title, col_id);
+        app.post_form("/entries", &body, Some(&author_cookie)).await;
+    }
+    let kept = entry_id(&app, "Kept").await;
+    let taken = entry_id(&app, "Taken").await;
+
+    let body = "url=example.com&title=Kept&duration=1&interval=days&tags=&collection_id=";
+    let resp = app.post_form(&format!("/entries/{}", kept), body, Some(&editor_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("Only the author or an admin can move this link"));
+    assert_eq!(entry_collection(&app, &kept).await.as_deref(), Some(col_id.as_str()));
+
+    let body = "url=example.com&title=Kept&duration=1&interval=days&tags=&collection_id=";
+    app.post_form(&format!("/entries/{}", kept), body, Some(&owner_cookie)).await;
+    assert_eq!(entry_collection(&app, &kept).await, None);
+    let body = "url=example.com&title=Taken&duration=1&interval=days&tags=&collection_id=";
+    app.post_form(&format!("/entries/{}", taken), body, Some(&author_cookie)).await;
+    assert_eq!(entry_collection(&app, &taken).await, None);
+}
+
+#[tokio::test]
+async fn reverting_a_move_checks_the_old_collection() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (author_id, author_invite) = app.create_user("Author").await;
+    let (editor_id, editor_invite) = app.create_user("Editor").await;
+    let author_cookie = app.login(&author_invite).await;
+    let editor_cookie = app.login(&editor_invite).await;
+    let first = app.create_collection(&owner_id, "First").await;
+    let second = app.create_collection(&owner_id, "Second").await;
+    for col_id in [&first, &second] {
+        app.add_member(col_id, &author_id).await;
+        app.add_member(col_id, &editor_id).await;
+    }
+
+    let body = format!("url=example.com&title=Moved&duration=1&interval=days&tags=&collection_id={}", first);
+    app.post_form("/entries", &body, Some(&author_cookie)).await;
+    let id = entry_id(&app, "Moved").await;
+    let body = format!("url=example.com&title=Moved&duration=1&interval=days&tags=&collection_id={}", second);
+    app.post_form(&format!("/entries/{}", id), &body, Some(&author_cookie)).await;
+    let (revision_id,): (String,) = sqlx::query_as("SELECT id FROM entry_revisions WHERE entry_id = ?")
+        .bind(&id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let revert = format!("/entries/{}/revisions/{}/revert", id, revision_id);
+
+    // Another editor can't take the author's link out of its collection
+    app.post_form(&revert, "", Some(&editor_cookie)).await;
+    assert_eq!(entry_collection(&app, &id).await.as_deref(), Some(second.as_str()));
+
+    // Nor can the author put it back somewhere they can no longer add to
+    app.set_role(&first, &author_id, "viewer").await;
+    app.post_form(&revert, "", Some(&author_cookie)).await;
+    assert_eq!(entry_collection(&app, &id).await.as_deref(), Some(second.as_str()));
+
+    app.set_role(&first, &author_id, "editor").await;
+    app.post_form(&revert, "", Some(&author_cookie)).await;
+    assert_eq!(entry_collection(&app, &id).await.as_deref(), Some(first.as_str()));
+}
+
+// --- Entries ---
+
+#[tokio::test]
+async fn owner_sees_entries_members_add() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("url=example.com&title=From+Member&duration=1&interval=days&tags=shared&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&member_cookie)).await;
+    let id = entry_id(&app, "From Member").await;
+
+    let html = body_string(app.get("/all", Some(&owner_cookie)).await).await;
+    assert!(html.contains("From Member"));
+    let html = body_string(app.get("/all?tags=shared", Some(&owner_cookie)).await).await;
+    assert!(html.contains("From Member"));
+    let resp = app.get(&format!("/entries/{}", id), Some(&owner_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.post_form(&format!("/entries/{}/visit", id), "", Some(&owner_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn show_collection_lists_its_entries() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&member_cookie)).await).await;
+    assert!(html.contains("No links in this collection yet."));
+
+    let body = format!("url=example.com&title=In+Collection&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let body = "url=example.org&title=Personal&duration=1&interval=days&tags=&collection_id=";
+    app.post_form("/entries", body, Some(&owner_cookie)).await;
+
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&member_cookie)).await).await;
+    assert!(html.contains("In Collection"));
+    assert!(!html.contains("Personal"));
+}
+
+#[tokio::test]
+async fn outsider_cannot_add_entries_to_collection() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (_, outsider_invite) = app.create_user("Outsider").await;
+    let cookie = app.login(&outsider_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    let body = format!("url=example.com&title=Injected&duration=1&interval=days&tags=&collection_id={}", col_id);
+    let resp = app.post_form("/entries", &body, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("add links to this collection"));
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entries WHERE collection_id = ?")
+        .bind(&col_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn outsider_cannot_move_entries_into_collection() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (_, outsider_invite) = app.create_user("Outsider").await;
+    let cookie = app.login(&outsider_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    let body = "url=example.com&title=Mine&duration=1&interval=days&tags=&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let id = entry_id(&app, "Mine").await;
+
+    let body = format!("url=example.com&title=Mine&duration=1&interval=days&tags=&collection_id={}", col_id);
+    let resp = app.post_form(&format!("/entries/{}", id), &body, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("add links to this collection"));
+    let (collection_id,): (Option<String>,) = sqlx::query_as("SELECT collection_id FROM entries WHERE id = ?")
+        .bind(&id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(collection_id, None);
+}
+
+#[tokio::test]
+async fn import_skips_default_collection_after_leaving() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("default_duration=1&default_interval=days&default_tags=&default_collection_id={}", col_id);
+    app.post_form("/settings", &body, Some(&cookie)).await;
+    let (default_collection_id,): (Option<String>,) =
+        sqlx::query_as("SELECT default_collection_id FROM user_settings WHERE user_id = ?")
+            .bind(&member_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(default_collection_id.as_deref(), Some(col_id.as_str()));
+    app.post_form(&format!("/collections/{}/leave", col_id), "", Some(&cookie)).await;
+
+    let path = std::env::temp_dir().join(format!("interne-import-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, r#"[{"id": "1", "url": "https://example.com", "title": "Imported"}]"#).unwrap();
+    interne::cli::import_data(&app.db, path.to_str().unwrap(), &member_id).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let (collection_id,): (Option<String>,) = sqlx::query_as("SELECT collection_id FROM entries WHERE title = ?")
+        .bind("Imported")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(collection_id, None);
+}
+
+// --- Invites ---
+
+async fn invite_code(app: &TestApp, collection_id: &str, label: &str) -> String {
+    let (code,): (String,) = sqlx::query_as("SELECT code FROM collection_invites WHERE collection_id = ? AND label = ?")
+        .bind(collection_id)
+        .bind(label)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    code
+}
+
+#[tokio::test]
+async fn new_collection_starts_with_an_invite() {
+    let app = TestApp::new().await;
+    let (_, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+
+    app.post_form("/collections", "name=Fresh", Some(&owner_cookie)).await;
+    let (col_id, code): (String, String) = sqlx::query_as(
+        "SELECT c.id, i.code FROM collections c JOIN collection_invites i ON i.collection_id = c.id WHERE c.name = 'Fresh'",
+    )
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&owner_cookie)).await).await;
+    assert!(html.contains(&code));
+
+    app.post_form("/collections/join", &format!("invite_code={}", code), Some(&member_cookie)).await;
+    assert_eq!(member_role(&app, &col_id, &member_id).await.as_deref(), Some("editor"));
+}
+
+#[tokio::test]
+async fn invite_sets_role_and_counts_uses() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (first_id, first_invite) = app.create_user("First").await;
+    let (second_id, second_invite) = app.create_user("Second").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let first_cookie = app.login(&first_invite).await;
+    let second_cookie = app.login(&second_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    app.post_form(
+        &format!("/collections/{}/invites", col_id),
+        "label=Readers&role=viewer&max_uses=1&expires_in_days=7",
+        Some(&owner_cookie),
+    )
+    .await;
+    let code = invite_code(&app, &col_id, "Readers").await;
+
+    let resp = app.post_form("/collections/join", &format!("invite_code={}", code), Some(&first_cookie)).await;
+    assert_redirect(&resp, "/collections");
+    assert_eq!(member_role(&app, &col_id, &first_id).await.as_deref(), Some("viewer"));
+
+    // Joining again doesn't use the invite up
+    app.post_form("/collections/join", &format!("invite_code={}", code), Some(&first_cookie)).await;
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&owner_cookie)).await).await;
+    assert!(html.contains("Readers"));
+    assert!(html.contains("1 of 1 use"));
+    assert!(html.contains("expires in 6 days"));
+
+    let resp = app.post_form("/collections/join", &format!("invite_code={}", code), Some(&second_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("This invite has no uses left"));
+    assert!(member_role(&app, &col_id, &second_id).await.is_none());
+}
+
+#[tokio::test]
+async fn expired_and_revoked_invites_are_rejected() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    app.add_invite(&col_id, "old-code").await;
+    sqlx::query("UPDATE collection_invites SET expires_at = ? WHERE code = 'old-code'")
+        .bind((chrono::Utc::now() - chrono::Duration::days(1)).to_rfc3339())
+        .execute(&app.db)
+        .await
+        .unwrap();
+    let resp = app.post_form("/collections/join", "invite_code=old-code", Some(&member_cookie)).await;
+    assert!(body_string(resp).await.contains("This invite has expired"));
+
+    app.add_invite(&col_id, "revoke-me").await;
+    let (invite_id,): (String,) = sqlx::query_as("SELECT id FROM collection_invites WHERE code = 'revoke-me'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let resp = app.delete(&format!("/collections/{}/invites/{}", col_id, invite_id), Some(&owner_cookie)).await;
+    assert_hx_redirect(&resp, &format!("/collections/{}", col_id));
+    let resp = app.post_form("/collections/join", "invite_code=revoke-me", Some(&member_cookie)).await;
+    assert!(body_string(resp).await.contains("That invite code isn"));
+
+    assert!(member_role(&app, &col_id, &member_id).await.is_none());
+}
+
+#[tokio::test]
+async fn only_admins_manage_invites() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (editor_id, editor_invite) = app.create_user("Editor").await;
+    let editor_cookie = app.login(&editor_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &editor_id).await;
+    app.add_invite(&col_id, "keep-me").await;
+
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&editor_cookie)).await).await;
+    assert!(!html.contains("keep-me"));
+
+    app.post_form(&format!("/collections/{}/invites", col_id), "label=Mine&role=admin&max_uses=&expires_in_days=", Some(&editor_cookie)).await;
+    let (id,): (String,) = sqlx::query_as("SELECT id FROM collection_invites WHERE code = 'keep-me'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.delete(&format!("/collections/{}/invites/{}", col_id, id), Some(&editor_cookie)).await;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM collection_invites WHERE collection_id = ?")
+        .bind(&col_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[tokio::test]
+async fn invalid_invite_limits_show_errors() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    let resp = app
+        .post_form(&format!("/collections/{}/invites", col_id), "label=&role=editor&max_uses=0&expires_in_days=soon", Some(&cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Max uses must be at least 1"));
+    assert!(html.contains("Expiry must be at least 1 day"));
+}
+
+// --- Ownership ---
+
+async fn collection_owner(app: &TestApp, collection_id: &str) -> Option<String> {
+    sqlx::query_as("SELECT owner_id FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_optional(&app.db)
+        .await
+        .unwrap()
+        .map(|(id,): (String,)| id)
+}
+
+#[tokio::test]
+async fn owner_transfers_ownership_to_member() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    // Members can't take ownership for themselves
+    app.post_form(&format!("/collections/{}/transfer", col_id), &format!("user_id={}", member_id), Some(&member_cookie))
+        .await;
+    assert_eq!(collection_owner(&app, &col_id).await.as_deref(), Some(owner_id.as_str()));
+
+    let resp = app
+        .post_form(&format!("/collections/{}/transfer", col_id), &format!("user_id={}", member_id), Some(&owner_cookie))
+        .await;
+    assert_redirect(&resp, &format!("/collections/{}", col_id));
+    assert_eq!(collection_owner(&app, &col_id).await.as_deref(), Some(member_id.as_str()));
+    assert!(member_role(&app, &col_id, &member_id).await.is_none());
+    assert_eq!(member_role(&app, &col_id, &owner_id).await.as_deref(), Some("admin"));
+
+    // The previous owner can now leave
+    app.post_form(&format!("/collections/{}/leave", col_id), "", Some(&owner_cookie)).await;
+    assert!(member_role(&app, &col_id, &owner_id).await.is_none());
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&member_cookie)).await).await;
+    assert!(html.contains("Your role: Owner"));
+    let html = body_string(app.get(&format!("/collections/{}/activity", col_id), Some(&member_cookie)).await).await;
+    assert!(html.contains("handed ownership to"));
+}
+
+#[tokio::test]
+async fn transfer_requires_a_member() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (outsider_id, _) = app.create_user("Outsider").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    app.post_form(&format!("/collections/{}/transfer", col_id), &format!("user_id={}", outsider_id), Some(&owner_cookie))
+        .await;
+    assert_eq!(collection_owner(&app, &col_id).await.as_deref(), Some(owner_id.as_str()));
+
+    // Access through a parent collection isn't membership
+    let parent_id = app.create_collection(&owner_id, "Company").await;
+    app.set_parent(&col_id, &parent_id).await;
+    app.add_member(&parent_id, &outsider_id).await;
+    app.post_form(&format!("/collections/{}/transfer", col_id), &format!("user_id={}", outsider_id), Some(&owner_cookie))
+        .await;
+    assert_eq!(collection_owner(&app, &col_id).await.as_deref(), Some(owner_id.as_str()));
+}
+
+#[tokio::test]
+async fn deleting_owner_passes_collection_to_senior_member() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (editor_id, _) = app.create_user("Editor").await;
+    let (admin_id, admin_invite) = app.create_user("Admin").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let admin_cookie = app.login(&admin_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &editor_id).await;
+    app.add_member(&col_id, &admin_id).await;
+    app.set_role(&col_id, &admin_id, "admin").await;
+
+    let body = format!("url=example.com&title=Kept&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&admin_cookie)).await;
+    let body = format!("url=example.org&title=Owners&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+
+    interne::cli::delete_user(&app.db, &owner_id).await.unwrap();
+
+    assert_eq!(collection_owner(&app, &col_id).await.as_deref(), Some(admin_id.as_str()));
+    assert!(member_role(&app, &col_id, &admin_id).await.is_none());
+    assert_eq!(member_role(&app, &col_id, &editor_id).await.as_deref(), Some("editor"));
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&admin_cookie)).await).await;
+    assert!(html.contains("Kept"));
+    // The departing owner's links pass to the new owner rather than cascading away
+    assert!(html.contains("Owners"));
+    let (author,): (String,) = sqlx::query_as("SELECT user_id FROM entries WHERE title = 'Owners'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(author, admin_id);
+}
+
+#[tokio::test]
+async fn deleting_sole_owner_keeps_former_members_links() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("url=example.com&title=Mine&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&member_cookie)).await;
+    app.post_form(&format!("/collections/{}/leave", col_id), "", Some(&member_cookie)).await;
+
+    interne::cli::delete_user(&app.db, &owner_id).await.unwrap();
+
+    assert!(collection_owner(&app, &col_id).await.is_none());
+    let (collection_id,): (Option<String>,) = sqlx::query_as("SELECT collection_id FROM entries WHERE title = 'Mine'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert!(collection_id.is_none());
+}
+
+// --- Defaults ---
+
+async fn entry_schedule(app: &TestApp, title: &str) -> (i64, String) {
+    sqlx::query_as("SELECT duration, interval FROM entries WHERE title = ?")
+        .bind(title)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+async fn entry_tags(app: &TestApp, title: &str) -> Vec<String> {
+    let tags: Vec<(String,)> = sqlx::query_as(
+        "SELECT t.name FROM tags t JOIN entry_tags et ON et.tag_id = t.id JOIN entries e ON e.id = et.entry_id WHERE e.title = ? ORDER BY t.name",
+    )
+    .bind(title)
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    tags.into_iter().map(|(name,)| name).collect()
+}
+
+#[tokio::test]
+async fn collection_defaults_apply_to_new_entries() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    let resp = app
+        .post_form(
+            &format!("/collections/{}", col_id),
+            "name=Team&default_duration=2&default_interval=weeks&default_tags=Team,+Reading",
+            Some(&cookie),
+        )
+        .await;
+    assert_redirect(&resp, "/collections");
+
+    let body = format!("url=example.com&title=Shared&duration=1&interval=days&tags=rust&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    assert_eq!(entry_schedule(&app, "Shared").await, (2, "weeks".to_string()));
+    assert_eq!(entry_tags(&app, "Shared").await, vec!["reading", "rust", "team"]);
+
+    // A schedule set on the entry itself wins
+    let body = format!("url=example.org&title=Custom&duration=3&interval=days&tags=&collection_id={}&schedule_set=true", col_id);
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    assert_eq!(entry_schedule(&app, "Custom").await, (3, "days".to_string()));
+
+    // Private entries don't get them
+    app.post_form("/entries", "url=example.net&title=Private&duration=1&interval=days&tags=&collection_id=", Some(&cookie))
+        .await;
+    assert_eq!(entry_schedule(&app, "Private").await, (1, "days".to_string()));
+    assert!(entry_tags(&app, "Private").await.is_empty());
+
+    let html = body_string(app.get(&format!("/collections/{}/edit", col_id), Some(&cookie)).await).await;
+    assert!(html.contains(r#"value="team, reading""#));
+}
+
+#[tokio::test]
+async fn reschedule_all_updates_collection_entries() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (viewer_id, viewer_invite) = app.create_user("Viewer").await;
+    let cookie = app.login(&owner_invite).await;
+    let viewer_cookie = app.login(&viewer_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &viewer_id).await;
+    app.set_role(&col_id, &viewer_id, "viewer").await;
+
+    for title in ["One", "Two"] {
+        let body = format!("url=example.com&title={}&duration=1&interval=days&tags=&collection_id={}", title, col_id);
+        app.post_form("/entries", &body, Some(&cookie)).await;
+    }
+    app.post_form("/entries", "url=example.net&title=Private&duration=1&interval=days&tags=&collection_id=", Some(&cookie))
+        .await;
+
+    // Viewers can't reschedule
+    app.post_form(&format!("/collections/{}/reschedule", col_id), "duration=3&interval=months", Some(&viewer_cookie))
+        .await;
+    assert_eq!(entry_schedule(&app, "One").await, (1, "days".to_string()));
+
+    let resp = app
+        .post_form(&format!("/collections/{}/reschedule", col_id), "duration=3&interval=months", Some(&cookie))
+        .await;
+    assert_redirect(&resp, &format!("/collections/{}", col_id));
+    assert_eq!(entry_schedule(&app, "One").await, (3, "months".to_string()));
+    assert_eq!(entry_schedule(&app, "Two").await, (3, "months".to_string()));
+    assert_eq!(entry_schedule(&app, "Private").await, (1, "days".to_string()));
+
+    let (revisions,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_revisions")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(revisions, 2);
+
+    let resp = app
+        .post_form(&format!("/collections/{}/reschedule", col_id), "duration=&interval=days", Some(&cookie))
+        .await;
+    assert!(body_string(resp).await.contains("Duration must be at least 1"));
+}
+
+// --- Activity ---
+
+#[tokio::test]
+async fn activity_feed_records_entry_and_member_events() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (_, member_invite) = app.create_user("Mallory").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_invite(&col_id, "team-code").await;
+
+    app.post_form("/collections/join", "invite_code=team-code", Some(&member_cookie)).await;
+    let body = format!("url=example.com&title=Shared+Link&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&member_cookie)).await;
+    let id = entry_id(&app, "Shared Link").await;
+    app.post_form(&format!("/entries/{}/visit", id), "", Some(&owner_cookie)).await;
+    let body = format!("url=example.com&title=Renamed&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form(&format!("/entries/{}", id), &body, Some(&owner_cookie)).await;
+    app.delete(&format!("/entries/{}", id), Some(&owner_cookie)).await;
+    app.post_form(&format!("/collections/{}/leave", col_id), "", Some(&member_cookie)).await;
+
+    let html = body_string(app.get(&format!("/collections/{}/activity", col_id), Some(&owner_cookie)).await).await;
+    let lines: Vec<String> = html
+        .split("activity-item")
+        .skip(1)
+        .map(|item| item.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].contains("Mallory</strong> left the collection"));
+    assert!(lines[1].contains("Owner</strong> removed Renamed"));
+    assert!(lines[2].contains("Owner</strong> edited Renamed"));
+    assert!(lines[3].contains("Owner</strong> visited Shared Link"));
+    assert!(lines[4].contains("Mallory</strong> added Shared Link"));
+    assert!(lines[5].contains("Mallory</strong> joined the collection"));
+
+    // Filtered to one member
+    let html = body_string(
+        app.get(&format!("/collections/{}/activity?member={}", col_id, owner_id), Some(&owner_cookie)).await,
+    )
+    .await;
+    assert_eq!(html.matches("activity-item").count(), 3);
+    assert!(!html.contains("Mallory"));
+}
+
+#[tokio::test]
+async fn activity_feed_pages_and_is_members_only() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (_, outsider_invite) = app.create_user("Outsider").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let outsider_cookie = app.login(&outsider_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    let html = body_string(app.get(&format!("/collections/{}/activity", col_id), Some(&owner_cookie)).await).await;
+    assert!(html.contains("No activity yet."));
+
+    for i in 0..25 {
+        let body = format!("url=example.com/{}&title=Link+{}&duration=1&interval=days&tags=&collection_id={}", i, i, col_id);
+        app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    }
+
+    let html = body_string(app.get(&format!("/collections/{}/activity", col_id), Some(&owner_cookie)).await).await;
+    assert_eq!(html.matches("activity-item").count(), 20);
+    assert!(html.contains("Link 24"));
+    // The next page starts after the oldest event shown
+    let (before,): (i64,) =
+        sqlx::query_as("SELECT rowid FROM collection_events ORDER BY rowid DESC LIMIT 1 OFFSET 19")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert!(html.contains(&format!("before={}", before)));
+
+    let html = body_string(
+        app.get(&format!("/collections/{}/activity?member=&before={}", col_id, before), Some(&owner_cookie)).await,
+    )
+    .await;
+    assert_eq!(html.matches("activity-item").count(), 5);
+    assert!(html.contains("Link 0"));
+    assert!(!html.contains("Older activity"));
+
+    let resp = app.get(&format!("/collections/{}/activity", col_id), Some(&outsider_cookie)).await;
+    assert_redirect(&resp, "/collections");
+}
+
+// --- Progress ---
+
+#[tokio::test]
+async fn progress_matrix_shows_each_members_visits() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Olivia").await;
+    let (member_id, member_invite) = app.create_user("Marcus").await;
+    let (_, outsider_invite) = app.create_user("Outsider").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let outsider_cookie = app.login(&outsider_invite).await;
+    let col_id = app.create_collection(&owner_id, "Reading").await;
+    app.add_member(&col_id, &member_id).await;
+
+    for title in ["Chapter+One", "Chapter+Two"] {
+        let body = format!("url=example.com&title={}&duration=1&interval=weeks&tags=&collection_id={}", title, col_id);
+        app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    }
+    let id = entry_id(&app, "Chapter One").await;
+    app.post_form(&format!("/entries/{}/visit", id), "", Some(&member_cookie)).await;
+
+    let resp = app.get(&format!("/collections/{}/progress", col_id), Some(&owner_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    let olivia = html.find("<th>Olivia</th>").unwrap();
+    let marcus = html.find("<th>Marcus</th>").unwrap();
+    assert!(olivia < marcus);
+    assert!(html.contains("just now"));
+    // Marcus has read one of the two; nobody else has read anything
+    assert!(html.contains("<td>1 of 2</td>"));
+    assert!(html.contains("<td>0 of 2</td>"));
+    assert_eq!(html.matches("class=\"progress-due\"").count(), 3);
+
+    let resp = app.get(&format!("/collections/{}/progress", col_id), Some(&outsider_cookie)).await;
+    assert_redirect(&resp, "/collections");
+}
+
+// --- Nesting ---
+
+async fn collection_parent(app: &TestApp, collection_id: &str) -> Option<String> {
+    let (parent_id,): (Option<String>,) = sqlx::query_as("SELECT parent_id FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    parent_id
+}
+
+#[tokio::test]
+async fn parent_members_can_use_subcollections() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    let design_id = app.create_collection(&owner_id, "Design").await;
+    app.set_parent(&design_id, &team_id).await;
+    app.add_member(&team_id, &member_id).await;
+
+    let body = format!("url=example.com&title=Mockups&duration=1&interval=days&tags=&collection_id={}", design_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let body = format!("url=example.org&title=Sketches&duration=1&interval=days&tags=&collection_id={}", design_id);
+    app.post_form("/entries", &body, Some(&member_cookie)).await;
+
+    let html = body_string(app.get("/all", Some(&member_cookie)).await).await;
+    assert!(html.contains("Mockups"));
+    assert!(html.contains("Sketches"));
+
+    let resp = app.get(&format!("/collections/{}", design_id), Some(&member_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Your role: Editor"));
+    assert!(html.contains(&format!("<a href=\"/collections/{}\">Team</a>", team_id)));
+
+    let html = body_string(app.get("/collections", Some(&member_cookie)).await).await;
+    assert!(html.contains("collection-depth-1"));
+    assert!(html.find("Team").unwrap() < html.find("Design").unwrap());
+    // Counts match the member list, which only has direct members
+    assert_eq!(html.matches("2 members").count(), 1);
+}
+
+#[tokio::test]
+async fn subcollection_members_cannot_see_the_parent() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let cookie = app.login(&member_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    let design_id = app.create_collection(&owner_id, "Design").await;
+    app.set_parent(&design_id, &team_id).await;
+    app.add_member(&design_id, &member_id).await;
+
+    let resp = app.get(&format!("/collections/{}", team_id), Some(&cookie)).await;
+    assert_redirect(&resp, "/collections");
+
+    let html = body_string(app.get(&format!("/collections/{}", design_id), Some(&cookie)).await).await;
+    assert!(!html.contains("Team"));
+    let html = body_string(app.get("/collections", Some(&cookie)).await).await;
+    assert!(html.contains("Design"));
+    assert!(!html.contains("collection-depth-1"));
+}
+
+#[tokio::test]
+async fn creating_a_subcollection_requires_managing_the_parent() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let cookie = app.login(&member_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&team_id, &member_id).await;
+
+    let body = format!("name=Side+Project&parent_id={}", team_id);
+    let resp = app.post_form("/collections", &body, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("inside ones you manage"));
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM collections WHERE parent_id = ?")
+        .bind(&team_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+
+    app.set_role(&team_id, &member_id, "admin").await;
+    let html = body_string(app.get(&format!("/collections/new?parent_id={}", team_id), Some(&cookie)).await).await;
+    assert!(html.contains(&format!("<option value=\"{}\" selected>Team</option>", team_id)));
+    let resp = app.post_form("/collections", &body, Some(&cookie)).await;
+    assert_redirect(&resp, "/collections");
+    let (sub_id,): (String,) = sqlx::query_as("SELECT id FROM collections WHERE name = 'Side Project'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(collection_parent(&app, &sub_id).await.as_deref(), Some(team_id.as_str()));
+}
+
+#[tokio::test]
+async fn collection_cannot_move_inside_its_own_subcollection() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    let design_id = app.create_collection(&owner_id, "Design").await;
+    app.set_parent(&design_id, &team_id).await;
+
+    let body = format!("name=Team&parent_id={}", design_id);
+    let resp = app.post_form(&format!("/collections/{}", team_id), &body, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("inside itself or one of its subcollections"));
+    assert_eq!(collection_parent(&app, &team_id).await, None);
+
+    // The edit form doesn't offer the collection's own subtree
+    let html = body_string(app.get(&format!("/collections/{}/edit", team_id), Some(&cookie)).await).await;
+    assert!(!html.contains(&format!("<option value=\"{}\"", design_id)));
+
+    let resp = app.post_form(&format!("/collections/{}", design_id), "name=Design&parent_id=", Some(&cookie)).await;
+    assert_redirect(&resp, "/collections");
+    assert_eq!(collection_parent(&app, &design_id).await, None);
+}
+
+#[tokio::test]
+async fn only_the_owner_sees_edit_errors() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (admin_id, admin_invite) = app.create_user("Admin").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let admin_cookie = app.login(&admin_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    let design_id = app.create_collection(&owner_id, "Design").await;
+    app.set_parent(&design_id, &team_id).await;
+    app.add_member(&team_id, &admin_id).await;
+    app.set_role(&team_id, &admin_id, "admin").await;
+
+    let body = format!("name=Team&parent_id={}", design_id);
+    let resp = app.post_form(&format!("/collections/{}", team_id), &body, Some(&admin_cookie)).await;
+    assert_redirect(&resp, "/collections");
+
+    // The owner gets the edit form back, not the new collection one
+    let resp = app.post_form(&format!("/collections/{}", team_id), &body, Some(&owner_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("inside itself or one of its subcollections"));
+    assert!(html.contains(&format!("action=\"/collections/{}\"", team_id)));
+}
+
+#[tokio::test]
+async fn links_can_be_filtered_by_collection_subtree() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    let design_id = app.create_collection(&owner_id, "Design").await;
+    let home_id = app.create_collection(&owner_id, "Home").await;
+    app.set_parent(&design_id, &team_id).await;
+
+    for (title, col_id) in [("Roadmap", &team_id), ("Mockups", &design_id), ("Recipes", &home_id)] {
+        let body = format!("url=example.com&title={}&duration=1&interval=days&tags=&collection_id={}", title, col_id);
+        app.post_form("/entries", &body, Some(&cookie)).await;
+    }
+
+    let html = body_string(app.get(&format!("/all?collection={}", team_id), Some(&cookie)).await).await;
+    assert!(html.contains("Roadmap"));
+    assert!(html.contains("Mockups"));
+    assert!(!html.contains("Recipes"));
+    assert!(html.contains("— Design</option>"));
+
+    let html = body_string(app.get(&format!("/all?collection={}", design_id), Some(&cookie)).await).await;
+    assert!(!html.contains("Roadmap"));
+    assert!(html.contains("Mockups"));
+}
+
+#[tokio::test]
+async fn deleting_a_parent_moves_subcollections_to_the_top_level() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let team_id = app.create_collection(&owner_id, "Team").await;
+    let design_id = app.create_collection(&owner_id, "Design").await;
+    app.set_parent(&design_id, &team_id).await;
+
+    let resp = app.delete(&format!("/collections/{}", team_id), Some(&cookie)).await;
+    assert_hx_redirect(&resp, "/collections");
+    assert_eq!(collection_parent(&app, &design_id).await, None);
+    let resp = app.get(&format!("/collections/{}", design_id), Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
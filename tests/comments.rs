@@ -0,0 +1,210 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{assert_hx_redirect, assert_redirect, body_string, TestApp};
+
+async fn entry_id(app: &TestApp, title: &str) -> String {
+    let (id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = ?")
+        .bind(title)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    id
+}
+
+async fn comment_id(app: &TestApp, body: &str) -> String {
+    let (id,): (String,) = sqlx::query_as("SELECT id FROM entry_comments WHERE body = ?")
+        .bind(body)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    id
+}
+
+async fn comment_count(app: &TestApp) -> i64 {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_comments")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    count
+}
+
+/// A collection with an owner and a member, and one entry in it
+async fn shared_entry(app: &TestApp) -> (String, String, String, String) {
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("url=example.com&title=Shared&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let id = entry_id(app, "Shared").await;
+    (col_id, id, owner_cookie, member_cookie)
+}
+
+#[tokio::test]
+async fn members_comment_and_reply() {
+    let app = TestApp::new().await;
+    let (col_id, id, owner_cookie, member_cookie) = shared_entry(&app).await;
+
+    let resp = app.post_form(&format!("/entries/{}/comments", id), "body=Is+this+*still*+current%3F", Some(&member_cookie)).await;
+    let question = comment_id(&app, "Is this *still* current?").await;
+    assert_redirect(&resp, &format!("/entries/{}#comment-{}", id, question));
+
+    let body = format!("body=Yes%2C+updated+last+week&parent_id={}", question);
+    app.post_form(&format!("/entries/{}/comments", id), &body, Some(&owner_cookie)).await;
+    let answer = comment_id(&app, "Yes, updated last week").await;
+    let (parent_id,): (Option<String>,) = sqlx::query_as("SELECT parent_id FROM entry_comments WHERE id = ?")
+        .bind(&answer)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(parent_id.as_deref(), Some(question.as_str()));
+
+    let html = body_string(app.get(&format!("/entries/{}", id), Some(&owner_cookie)).await).await;
+    assert!(html.contains("Comments (2)"));
+    assert!(html.contains("Is this <em>still</em> current?"));
+    assert!(html.contains(&format!(r#"class="comment comment-indent-1" id="comment-{}""#, answer)));
+    assert!(html.find(&question).unwrap() < html.find(&answer).unwrap());
+
+    // Entry cards link to the discussion
+    let html = body_string(app.get(&format!("/collections/{}", col_id), Some(&member_cookie)).await).await;
+    assert!(html.contains("2 comments"));
+    let html = body_string(app.get("/all", Some(&member_cookie)).await).await;
+    assert!(html.contains(&format!(r##"<a href="/entries/{}#comments">2 comments</a>"##, id)));
+}
+
+#[tokio::test]
+async fn viewers_can_comment() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (viewer_id, viewer_invite) = app.create_user("Viewer").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let viewer_cookie = app.login(&viewer_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &viewer_id).await;
+    app.set_role(&col_id, &viewer_id, "viewer").await;
+
+    let body = format!("url=example.com&title=Shared&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let id = entry_id(&app, "Shared").await;
+
+    app.post_form(&format!("/entries/{}/comments", id), "body=Nice", Some(&viewer_cookie)).await;
+    assert_eq!(comment_count(&app).await, 1);
+}
+
+#[tokio::test]
+async fn outsiders_cannot_read_or_comment() {
+    let app = TestApp::new().await;
+    let (_, id, owner_cookie, _) = shared_entry(&app).await;
+    let (_, outsider_invite) = app.create_user("Outsider").await;
+    let outsider_cookie = app.login(&outsider_invite).await;
+
+    app.post_form(&format!("/entries/{}/comments", id), "body=Members+only", Some(&owner_cookie)).await;
+
+    let resp = app.post_form(&format!("/entries/{}/comments", id), "body=Sneaky", Some(&outsider_cookie)).await;
+    assert_redirect(&resp, "/");
+    assert_eq!(comment_count(&app).await, 1);
+    let resp = app.get(&format!("/entries/{}", id), Some(&outsider_cookie)).await;
+    assert_redirect(&resp, "/");
+}
+
+#[tokio::test]
+async fn private_entries_have_no_comments() {
+    let app = TestApp::new().await;
+    let (_, invite) = app.create_user("Solo").await;
+    let cookie = app.login(&invite).await;
+
+    let body = "url=example.com&title=Private&duration=1&interval=days&tags=&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let id = entry_id(&app, "Private").await;
+
+    let html = body_string(app.get(&format!("/entries/{}", id), Some(&cookie)).await).await;
+    assert!(!html.contains("Comments ("));
+    app.post_form(&format!("/entries/{}/comments", id), "body=Hello", Some(&cookie)).await;
+    assert_eq!(comment_count(&app).await, 0);
+}
+
+#[tokio::test]
+async fn replies_must_be_to_the_same_entry() {
+    let app = TestApp::new().await;
+    let (col_id, id, owner_cookie, _) = shared_entry(&app).await;
+    let body = format!("url=example.org&title=Other&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+    let other_id = entry_id(&app, "Other").await;
+
+    app.post_form(&format!("/entries/{}/comments", other_id), "body=Elsewhere", Some(&owner_cookie)).await;
+    let elsewhere = comment_id(&app, "Elsewhere").await;
+
+    let body = format!("body=Cross+reply&parent_id={}", elsewhere);
+    app.post_form(&format!("/entries/{}/comments", id), &body, Some(&owner_cookie)).await;
+    assert_eq!(comment_count(&app).await, 1);
+}
+
+#[tokio::test]
+async fn only_the_author_edits_or_deletes() {
+    let app = TestApp::new().await;
+    let (_, id, owner_cookie, member_cookie) = shared_entry(&app).await;
+
+    app.post_form(&format!("/entries/{}/comments", id), "body=First+take", Some(&member_cookie)).await;
+    let comment = comment_id(&app, "First take").await;
+
+    // The owner can't change or remove someone else's comment
+    let html = body_string(app.get(&format!("/entries/{}", id), Some(&owner_cookie)).await).await;
+    assert!(!html.contains("Save Comment"));
+    app.post_form(&format!("/entries/{}/comments/{}", id, comment), "body=Rewritten", Some(&owner_cookie)).await;
+    app.delete(&format!("/entries/{}/comments/{}", id, comment), Some(&owner_cookie)).await;
+    assert_eq!(comment_id(&app, "First take").await, comment);
+
+    let resp = app.post_form(&format!("/entries/{}/comments/{}", id, comment), "body=Second+take", Some(&member_cookie)).await;
+    assert_redirect(&resp, &format!("/entries/{}#comment-{}", id, comment));
+    let html = body_string(app.get(&format!("/entries/{}", id), Some(&member_cookie)).await).await;
+    assert!(html.contains("Second take"));
+    assert!(html.contains("edited"));
+
+    let resp = app.delete(&format!("/entries/{}/comments/{}", id, comment), Some(&member_cookie)).await;
+    assert_hx_redirect(&resp, &format!("/entries/{}", id));
+    assert_eq!(comment_count(&app).await, 0);
+}
+
+#[tokio::test]
+async fn comments_over_the_limit_are_rejected() {
+    let app = TestApp::new().await;
+    let (_, id, _, member_cookie) = shared_entry(&app).await;
+
+    let too_long = format!("body={}", "a".repeat(5001));
+    let resp = app.post_form(&format!("/entries/{}/comments", id), &too_long, Some(&member_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(comment_count(&app).await, 0);
+
+    app.post_form(&format!("/entries/{}/comments", id), "body=Short", Some(&member_cookie)).await;
+    let comment = comment_id(&app, "Short").await;
+    let resp = app.post_form(&format!("/entries/{}/comments/{}", id, comment), &too_long, Some(&member_cookie)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(comment_id(&app, "Short").await, comment);
+}
+
+#[tokio::test]
+async fn deleting_a_comment_with_replies_keeps_the_thread() {
+    let app = TestApp::new().await;
+    let (_, id, owner_cookie, member_cookie) = shared_entry(&app).await;
+
+    app.post_form(&format!("/entries/{}/comments", id), "body=Question", Some(&member_cookie)).await;
+    let question = comment_id(&app, "Question").await;
+    let body = format!("body=Answer&parent_id={}", question);
+    app.post_form(&format!("/entries/{}/comments", id), &body, Some(&owner_cookie)).await;
+    let answer = comment_id(&app, "Answer").await;
+
+    app.delete(&format!("/entries/{}/comments/{}", id, question), Some(&member_cookie)).await;
+    let html = body_string(app.get(&format!("/entries/{}", id), Some(&owner_cookie)).await).await;
+    assert!(html.contains("Comment deleted"));
+    assert!(!html.contains("Question"));
+    assert!(html.contains("Answer"));
+    assert!(html.contains("Comments (1)"));
+
+    // Once the last reply goes, so does the placeholder
+    app.delete(&format!("/entries/{}/comments/{}", id, answer), Some(&owner_cookie)).await;
+    assert_eq!(comment_count(&app).await, 0);
+}
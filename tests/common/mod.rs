@@ -64,6 +64,75 @@ impl TestApp {
         (id, invite_code)
     }
 
+    /// Create a collection owned by the given user and return its id. Like
+    /// the app, it starts with one open invite using `invite_code`.
+    pub async fn create_collection(&self, owner_id: &str, name: &str) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        let invite_code = uuid::Uuid::new_v4().to_string();
+        let now = chrono::Utc::now().to_rfc3339();
+
+        sqlx::query(
+            "INSERT INTO collections (id, owner_id, name, invite_code, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(owner_id)
+        .bind(name)
+        .bind(&invite_code)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.db)
+        .await
+        .expect("Failed to create test collection");
+
+        self.add_invite(&id, &invite_code).await;
+
+        id
+    }
+
+    /// Add an open invite (editor role, no limits) to a collection.
+    pub async fn add_invite(&self, collection_id: &str, code: &str) {
+        sqlx::query("INSERT INTO collection_invites (id, collection_id, code, created_at) VALUES (?, ?, ?, ?)")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(collection_id)
+            .bind(code)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&self.db)
+            .await
+            .expect("Failed to add collection invite");
+    }
+
+    /// Add a user to a collection as a member.
+    pub async fn add_member(&self, collection_id: &str, user_id: &str) {
+        sqlx::query("INSERT INTO collection_members (collection_id, user_id, joined_at) VALUES (?, ?, ?)")
+            .bind(collection_id)
+            .bind(user_id)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&self.db)
+            .await
+            .expect("Failed to add collection member");
+    }
+
+    /// Nest a collection under another one.
+    pub async fn set_parent(&self, collection_id: &str, parent_id: &str) {
+        sqlx::query("UPDATE collections SET parent_id = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(collection_id)
+            .execute(&self.db)
+            .await
+            .expect("Failed to set collection parent");
+    }
+
+    /// Change a member's role in a collection (`viewer`, `editor` or `admin`).
+    pub async fn set_role(&self, collection_id: &str, user_id: &str, role: &str) {
+        sqlx::query("UPDATE collection_members SET role = ? WHERE collection_id = ? AND user_id = ?")
+            .bind(role)
+            .bind(collection_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await
+            .expect("Failed to set member role");
+    }
+
     /// Log in as the given user and return the session cookie string.
     pub async fn login(&self, invite_code: &str) -> String {
         let req = Request::builder()
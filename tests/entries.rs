@@ -750,3 +750,495 @@ async fn create_entry_with_long_description_shows_error() {
     let html = body_string(resp).await;
     assert!(html.contains("Description must be under 5000 characters"));
 }
+
+#[tokio::test]
+async fn description_renders_sanitized_markdown() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=https%3A%2F%2Fexample.com&title=Markdown&description=**bold**+%3Cscript%3Ealert(1)%3C%2Fscript%3E&duration=3&interval=days&tags=&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let resp = app.get("/", Some(&cookie)).await;
+    let html = body_string(resp).await;
+    assert!(html.contains("<strong>bold</strong>"));
+    assert!(!html.contains("<script>alert(1)</script>"));
+}
+
+#[tokio::test]
+async fn visit_with_note_appears_in_history() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let entry_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO entries (id, user_id, url, title, duration, interval, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&entry_id)
+    .bind(&user_id)
+    .bind("https://example.com")
+    .bind("With Notes")
+    .bind(3)
+    .bind("days")
+    .bind(&now)
+    .bind(&now)
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    let resp = app
+        .post_form(
+            &format!("/entries/{}/visits", entry_id),
+            "note=The+*second*+read+was+clearer",
+            Some(&cookie),
+        )
+        .await;
+    assert_redirect(&resp, &format!("/entries/{}", entry_id));
+
+    let (note, dismissed_at): (Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT v.note, e.dismissed_at FROM visits v JOIN entries e ON e.id = v.entry_id WHERE v.entry_id = ?",
+    )
+    .bind(&entry_id)
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(note.as_deref(), Some("The *second* read was clearer"));
+    assert!(dismissed_at.is_some());
+
+    let resp = app
+        .get(&format!("/entries/{}", entry_id), Some(&cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("<em>second</em>"));
+    assert!(html.contains("History (1)"));
+}
+
+#[tokio::test]
+async fn visit_notes_over_the_limit_are_rejected() {
+    let app = TestApp::new().await;
+    let (_, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Long+Notes&duration=1&interval=days&tags=&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Long Notes'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    let too_long = format!("note={}", "a".repeat(5001));
+    let resp = app.post_form(&format!("/entries/{}/visits", entry_id), &too_long, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    assert!(body_string(resp).await.contains("Note must be under 5000 characters"));
+
+    app.post_form(&format!("/entries/{}/visits", entry_id), "note=Short", Some(&cookie)).await;
+    let (visit_id, visits): (String, i64) =
+        sqlx::query_as("SELECT MAX(id), COUNT(*) FROM visits WHERE entry_id = ?")
+            .bind(&entry_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(visits, 1);
+
+    let resp = app.post_form(&format!("/entries/{}/visits/{}", entry_id, visit_id), &too_long, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let (note,): (Option<String>,) = sqlx::query_as("SELECT note FROM visits WHERE id = ?")
+        .bind(&visit_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(note.as_deref(), Some("Short"));
+}
+
+#[tokio::test]
+async fn search_matches_visit_notes() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    for (title, note) in [("First Article", "borrow checker"), ("Second Article", "async runtimes")] {
+        let entry_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO entries (id, user_id, url, title, duration, interval, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&entry_id)
+        .bind(&user_id)
+        .bind("https://example.com")
+        .bind(title)
+        .bind(3)
+        .bind("days")
+        .bind(&now)
+        .bind(&now)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO visits (id, entry_id, user_id, visited_at, note) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&entry_id)
+        .bind(&user_id)
+        .bind(&now)
+        .bind(note)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    }
+
+    let resp = app.get("/all?q=borrow", Some(&cookie)).await;
+    let html = body_string(resp).await;
+    assert!(html.contains("First Article"));
+    assert!(!html.contains("Second Article"));
+}
+
+#[tokio::test]
+async fn update_entry_records_revision() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=https%3A%2F%2Fexample.com&title=Before&description=&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    let body = "url=https%3A%2F%2Fexample.com&title=After&description=&duration=3&interval=days&tags=rust%2C+web&collection_id=";
+    app.post_form(&format!("/entries/{}", entry_id), body, Some(&cookie))
+        .await;
+
+    let (revision_user, changes): (String, String) =
+        sqlx::query_as("SELECT user_id, changes FROM entry_revisions WHERE entry_id = ?")
+            .bind(&entry_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(revision_user, user_id);
+    let changes: serde_json::Value = serde_json::from_str(&changes).unwrap();
+    let fields: Vec<&str> = changes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, vec!["title", "tags"]);
+
+    let resp = app
+        .get(&format!("/entries/{}", entry_id), Some(&cookie))
+        .await;
+    let html = body_string(resp).await;
+    assert!(html.contains("Edits (1)"));
+    assert!(html.contains("<del>Before</del>"));
+
+    // Saving without changes does not add a revision
+    app.post_form(&format!("/entries/{}", entry_id), body, Some(&cookie))
+        .await;
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_revisions WHERE entry_id = ?")
+        .bind(&entry_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 1);
+}
+
+#[tokio::test]
+async fn revert_restores_state_before_revision() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=https%3A%2F%2Fexample.com&title=First&description=&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    let edits = [
+        "url=https%3A%2F%2Fexample.com&title=Second&description=&duration=5&interval=weeks&tags=python&collection_id=",
+        "url=https%3A%2F%2Fexample.com&title=Third&description=&duration=5&interval=weeks&tags=python&collection_id=",
+    ];
+    for body in edits {
+        app.post_form(&format!("/entries/{}", entry_id), body, Some(&cookie))
+            .await;
+    }
+
+    let (first_revision,): (String,) = sqlx::query_as(
+        "SELECT id FROM entry_revisions WHERE entry_id = ? ORDER BY created_at ASC LIMIT 1",
+    )
+    .bind(&entry_id)
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+
+    let resp = app
+        .post_form(
+            &format!("/entries/{}/revisions/{}/revert", entry_id, first_revision),
+            "",
+            Some(&cookie),
+        )
+        .await;
+    assert_redirect(&resp, &format!("/entries/{}", entry_id));
+
+    let (title, duration, interval): (String, i64, String) =
+        sqlx::query_as("SELECT title, duration, interval FROM entries WHERE id = ?")
+            .bind(&entry_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(title, "First");
+    assert_eq!(duration, 3);
+    assert_eq!(interval, "days");
+
+    let tags: Vec<(String,)> = sqlx::query_as(
+        "SELECT t.name FROM tags t JOIN entry_tags et ON et.tag_id = t.id WHERE et.entry_id = ?",
+    )
+    .bind(&entry_id)
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(tags, vec![("rust".to_string(),)]);
+
+    // The revert itself is recorded as a new revision
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_revisions WHERE entry_id = ?")
+        .bind(&entry_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 3);
+}
+
+#[tokio::test]
+async fn quick_add_form_is_prefilled() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app
+        .get(
+            "/entries/quick-add?url=https%3A%2F%2Fexample.com%2Farticle&title=An+Article&tags=rust",
+            Some(&cookie),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("value=\"https://example.com/article\""));
+    assert!(html.contains("value=\"An Article\""));
+    assert!(html.contains("value=\"rust\""));
+    assert!(html.contains("action=\"/entries\""));
+}
+
+#[tokio::test]
+async fn quick_add_post_saves_entry() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app
+        .post_form(
+            "/entries/quick-add",
+            "url=example.com%2Fquick&tags=later",
+            Some(&cookie),
+        )
+        .await;
+    assert_redirect(&resp, "/");
+
+    let (url, title, duration, interval): (String, String, i64, String) = sqlx::query_as(
+        "SELECT url, title, duration, interval FROM entries WHERE user_id = ?",
+    )
+    .bind(&user_id)
+    .fetch_one(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(url, "https://example.com/quick");
+    // Title falls back to the URL as given
+    assert_eq!(title, "example.com/quick");
+    assert_eq!(duration, 1);
+    assert_eq!(interval, "days");
+}
+
+#[tokio::test]
+async fn quick_add_post_with_invalid_url_shows_form() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app
+        .post_form("/entries/quick-add", "url=not+a+url", Some(&cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Please enter a valid URL"));
+}
+
+#[tokio::test]
+async fn create_entry_error_keeps_submitted_values() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=https%3A%2F%2Fexample.com&title=&description=Keep+me&duration=4&interval=weeks&tags=rust&collection_id=";
+    let resp = app.post_form("/entries", body, Some(&cookie)).await;
+    let html = body_string(resp).await;
+    assert!(html.contains("Keep me"));
+    assert!(html.contains("<option value=\"weeks\" selected>"));
+}
+
+#[tokio::test]
+async fn create_entry_saves_priority_and_pin() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Important&description=&duration=3&interval=days&priority=high&pinned=true&tags=&collection_id=";
+    let resp = app.post_form("/entries", body, Some(&cookie)).await;
+    assert_redirect(&resp, "/");
+
+    let (priority, pinned): (String, bool) =
+        sqlx::query_as("SELECT priority, pinned FROM entries WHERE user_id = ?")
+            .bind(&user_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(priority, "high");
+    assert!(pinned);
+}
+
+/// Insert a ready entry with the given priority and pin flag
+async fn insert_ranked_entry(app: &TestApp, user_id: &str, title: &str, priority: &str, pinned: bool, dismissed_at: Option<&str>) {
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO entries (id, user_id, url, title, duration, interval, priority, pinned, dismissed_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind("https://example.com")
+    .bind(title)
+    .bind(3)
+    .bind("days")
+    .bind(priority)
+    .bind(pinned)
+    .bind(dismissed_at)
+    .bind(&now)
+    .bind(&now)
+    .execute(&app.db)
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn ready_list_orders_pinned_then_priority() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let just_now = chrono::Utc::now().to_rfc3339();
+    insert_ranked_entry(&app, &user_id, "Low Entry", "low", false, None).await;
+    insert_ranked_entry(&app, &user_id, "High Entry", "high", false, None).await;
+    insert_ranked_entry(&app, &user_id, "Normal Entry", "normal", false, None).await;
+    insert_ranked_entry(&app, &user_id, "Pinned Entry", "low", true, None).await;
+    insert_ranked_entry(&app, &user_id, "Waiting Entry", "high", false, Some(&just_now)).await;
+    insert_ranked_entry(&app, &user_id, "Pinned Later", "low", true, Some(&just_now)).await;
+
+    let resp = app.get("/", Some(&cookie)).await;
+    let html = body_string(resp).await;
+    let pos = |title: &str| html.find(title).unwrap_or_else(|| panic!("{title} missing"));
+    assert!(pos("Pinned Entry") < pos("High Entry"));
+    assert!(pos("High Entry") < pos("Normal Entry"));
+    assert!(pos("Normal Entry") < pos("Low Entry"));
+    // Pinned entries that aren't due yet stay in Waiting, at the top
+    assert!(!html.contains("Pinned Later"));
+
+    let resp = app.get("/waiting", Some(&cookie)).await;
+    let html = body_string(resp).await;
+    let pos = |title: &str| html.find(title).unwrap_or_else(|| panic!("{title} missing"));
+    assert!(pos("Pinned Later") < pos("Waiting Entry"));
+    assert!(!html.contains("Pinned Entry"));
+}
+
+#[tokio::test]
+async fn list_filters_by_priority() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    insert_ranked_entry(&app, &user_id, "High Entry", "high", false, None).await;
+    insert_ranked_entry(&app, &user_id, "Low Entry", "low", false, None).await;
+
+    let resp = app.get("/all?priority=high", Some(&cookie)).await;
+    let html = body_string(resp).await;
+    assert!(html.contains("High Entry"));
+    assert!(!html.contains("Low Entry"));
+
+    // An empty filter shows everything
+    let resp = app.get("/all?q=&priority=", Some(&cookie)).await;
+    let html = body_string(resp).await;
+    assert!(html.contains("High Entry"));
+    assert!(html.contains("Low Entry"));
+}
+
+async fn create_tagged_entry(app: &TestApp, cookie: &str, title: &str, tags: &str) {
+    let body = format!(
+        "url=example.com&title={}&duration=3&interval=days&tags={}&collection_id=",
+        title.replace(' ', "+"),
+        tags.replace(',', "%2C").replace(' ', "+")
+    );
+    let resp = app.post_form("/entries", &body, Some(cookie)).await;
+    assert_redirect(&resp, "/");
+}
+
+#[tokio::test]
+async fn list_filters_by_boolean_tags() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    create_tagged_entry(&app, &cookie, "Rust Web", "rust, web").await;
+    create_tagged_entry(&app, &cookie, "Rust Only", "rust").await;
+    create_tagged_entry(&app, &cookie, "Old Rust Web", "rust, web, archived").await;
+    create_tagged_entry(&app, &cookie, "Go Web", "go, web").await;
+
+    // A literal "+" in the URL is the AND prefix
+    let html = body_string(app.get("/all?tags=rust,+web,-archived", Some(&cookie)).await).await;
+    assert!(html.contains("Rust Web"));
+    assert!(!html.contains("Rust Only"));
+    assert!(!html.contains("Old Rust Web"));
+    assert!(!html.contains("Go Web"));
+
+    // Bare terms are ORed
+    let html = body_string(app.get("/all?tags=rust%2C+go", Some(&cookie)).await).await;
+    assert!(html.contains("Rust Only"));
+    assert!(html.contains("Go Web"));
+
+    // Combines with the availability filter: nothing has been visited, so
+    // everything is ready and nothing is waiting
+    let html = body_string(app.get("/?tags=go", Some(&cookie)).await).await;
+    assert!(html.contains("Go Web"));
+    assert!(!html.contains("Rust Only"));
+    let html = body_string(app.get("/waiting?tags=go", Some(&cookie)).await).await;
+    assert!(!html.contains("Go Web"));
+}
+
+#[tokio::test]
+async fn tag_filter_shows_chips_and_keeps_filter_in_links() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let html = body_string(app.get("/all?tags=rust,+web", Some(&cookie)).await).await;
+    assert!(html.contains("class=\"tag-chip\">rust <a href=\"/all?tags=%2Bweb\""));
+    assert!(html.contains("class=\"tag-chip\">+web <a href=\"/all?tags=rust\""));
+    assert!(html.contains("href=\"/waiting?tags=rust%2C%2Bweb\""));
+    assert!(html.contains("value=\"rust, +web\""));
+}
@@ -28,9 +28,10 @@ async fn export_returns_json_with_entries() {
     .unwrap();
 
     let tag_id = uuid::Uuid::new_v4().to_string();
-    sqlx::query("INSERT INTO tags (id, name, created_at) VALUES (?, ?, ?)")
+    sqlx::query("INSERT INTO tags (id, name, user_id, created_at) VALUES (?, ?, ?, ?)")
         .bind(&tag_id)
         .bind("rust")
+        .bind(&user_id)
         .bind(&now)
         .execute(&app.db)
         .await
@@ -64,6 +65,8 @@ async fn export_returns_json_with_entries() {
     assert_eq!(json["entries"].as_array().unwrap().len(), 1);
     assert_eq!(json["entries"][0]["title"], "Export Test");
     assert_eq!(json["entries"][0]["tags"][0], "rust");
+    assert_eq!(json["entries"][0]["priority"], "normal");
+    assert_eq!(json["entries"][0]["pinned"], false);
 }
 
 #[tokio::test]
@@ -72,3 +75,326 @@ async fn export_unauthenticated_redirects() {
     let resp = app.get("/export", None).await;
     common::assert_redirect(&resp, "/login");
 }
+
+#[tokio::test]
+async fn export_includes_visit_notes() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let entry_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    sqlx::query(
+        "INSERT INTO entries (id, user_id, url, title, duration, interval, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&entry_id)
+    .bind(&user_id)
+    .bind("https://example.com")
+    .bind("Noted")
+    .bind(3)
+    .bind("days")
+    .bind(&now)
+    .bind(&now)
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    app.post_form(
+        &format!("/entries/{}/visits", entry_id),
+        "note=Learned+about+lifetimes",
+        Some(&cookie),
+    )
+    .await;
+
+    let resp = app.get("/export", Some(&cookie)).await;
+    let body = body_string(resp).await;
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(json["entries"][0]["notes"][0]["note"], "Learned about lifetimes");
+}
+
+#[tokio::test]
+async fn export_leaves_out_other_members_notes() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let cookie = app.login(&invite_code).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&user_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("url=example.com&title=Shared&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Shared'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form(&format!("/entries/{}/visits", entry_id), "note=Mine", Some(&cookie)).await;
+    app.post_form(&format!("/entries/{}/visits", entry_id), "note=Private+thoughts", Some(&member_cookie))
+        .await;
+
+    let body = body_string(app.get("/export", Some(&cookie)).await).await;
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(json["entries"][0]["notes"].as_array().unwrap().len(), 1);
+    assert_eq!(json["entries"][0]["notes"][0]["note"], "Mine");
+    assert!(!body.contains("Private thoughts"));
+}
+
+#[tokio::test]
+async fn export_includes_comments() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let cookie = app.login(&invite_code).await;
+    let member_cookie = app.login(&member_invite).await;
+    let col_id = app.create_collection(&user_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("url=example.com&title=Discussed&duration=1&interval=days&tags=&collection_id={}", col_id);
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Discussed'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form(&format!("/entries/{}/comments", entry_id), "body=Worth+a+read", Some(&member_cookie))
+        .await;
+
+    let resp = app.get("/export", Some(&cookie)).await;
+    let body = body_string(resp).await;
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    let comment = &json["entries"][0]["comments"][0];
+    assert_eq!(comment["author"], "Member");
+    assert_eq!(comment["body"], "Worth a read");
+    assert!(comment["parent_id"].is_null());
+    assert!(comment.get("entry_id").is_none());
+}
+
+/// A collection with default schedule and tags, a scheduled tag, and one
+/// entry that the owner has visited and commented on
+async fn collection_with_history(app: &TestApp) -> (String, String, String) {
+    let (user_id, invite_code) = app.create_user("Owner").await;
+    let cookie = app.login(&invite_code).await;
+    let body = "name=Team+Reading&default_duration=2&default_interval=weeks&default_tags=team";
+    app.post_form("/collections", body, Some(&cookie)).await;
+    let (col_id,): (String,) = sqlx::query_as("SELECT id FROM collections WHERE name = 'Team Reading'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    let body = format!(
+        "url=example.com&title=Essay&description=Long+read&duration=3&interval=days&tags=essays%2C+rust&collection_id={}&schedule_set=true",
+        col_id
+    );
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Essay'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form(&format!("/entries/{}/visits", entry_id), "note=Good+one", Some(&cookie)).await;
+    app.post_form(&format!("/entries/{}/comments", entry_id), "body=Discuss", Some(&cookie)).await;
+
+    let (tag_id,): (String,) = sqlx::query_as("SELECT id FROM tags WHERE name = 'rust' AND collection_id = ?")
+        .bind(&col_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form(&format!("/tag-manager/{}/schedule", tag_id), "default_duration=1&default_interval=months", Some(&cookie))
+        .await;
+
+    (user_id, col_id, cookie)
+}
+
+#[tokio::test]
+async fn collection_export_bundles_entries_tags_and_schedules() {
+    let app = TestApp::new().await;
+    let (_, col_id, cookie) = collection_with_history(&app).await;
+
+    let resp = app.get(&format!("/collections/{}/export", col_id), Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let content_disposition = resp.headers()["content-disposition"].to_str().unwrap().to_string();
+    assert!(content_disposition.contains("interne-collection-team-reading-"));
+    let json: serde_json::Value = serde_json::from_str(&body_string(resp).await).unwrap();
+
+    assert_eq!(json["format"], "interne-collection");
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["collection"]["name"], "Team Reading");
+    assert_eq!(json["collection"]["default_duration"], 2);
+    assert_eq!(json["collection"]["default_interval"], "weeks");
+    assert_eq!(json["collection"]["default_tags"], "team");
+    assert_eq!(json["tags"][0]["name"], "rust");
+    assert_eq!(json["tags"][0]["default_interval"], "months");
+
+    let entry = &json["entries"][0];
+    assert_eq!(entry["title"], "Essay");
+    assert_eq!(entry["duration"], 3);
+    assert_eq!(entry["tags"], serde_json::json!(["essays", "rust", "team"]));
+    assert_eq!(entry["comments"][0]["body"], "Discuss");
+    // Visit history is opt-in
+    assert!(entry.get("visits").is_none());
+    assert!(entry.get("dismissed_at").is_none());
+
+    let resp = app.get(&format!("/collections/{}/export?visits=true", col_id), Some(&cookie)).await;
+    let json: serde_json::Value = serde_json::from_str(&body_string(resp).await).unwrap();
+    let entry = &json["entries"][0];
+    assert_eq!(entry["visits"][0]["user"], "Owner");
+    assert_eq!(entry["visits"][0]["note"], "Good one");
+    assert!(entry["dismissed_at"].is_string());
+}
+
+#[tokio::test]
+async fn only_admins_export_other_members_visits() {
+    let app = TestApp::new().await;
+    let (_, col_id, _) = collection_with_history(&app).await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let member_cookie = app.login(&member_invite).await;
+    app.add_member(&col_id, &member_id).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Essay'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form(&format!("/entries/{}/visits", entry_id), "note=Mine", Some(&member_cookie)).await;
+
+    let visitors = |json: &serde_json::Value| -> Vec<String> {
+        json["entries"][0]["visits"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v["user"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let url = format!("/collections/{}/export?visits=true", col_id);
+
+    let json: serde_json::Value = serde_json::from_str(&body_string(app.get(&url, Some(&member_cookie)).await).await).unwrap();
+    assert_eq!(visitors(&json), vec!["Member"]);
+    assert_eq!(json["entries"][0]["visits"][0]["note"], "Mine");
+
+    app.set_role(&col_id, &member_id, "admin").await;
+    let json: serde_json::Value = serde_json::from_str(&body_string(app.get(&url, Some(&member_cookie)).await).await).unwrap();
+    assert_eq!(visitors(&json), vec!["Owner", "Member"]);
+}
+
+#[tokio::test]
+async fn collection_export_requires_membership() {
+    let app = TestApp::new().await;
+    let (_, col_id, _) = collection_with_history(&app).await;
+    let (_, outsider_invite) = app.create_user("Outsider").await;
+    let cookie = app.login(&outsider_invite).await;
+
+    let resp = app.get(&format!("/collections/{}/export", col_id), Some(&cookie)).await;
+    common::assert_redirect(&resp, "/collections");
+}
+
+#[tokio::test]
+async fn imported_bundle_becomes_new_collection() {
+    let app = TestApp::new().await;
+    let (_, col_id, cookie) = collection_with_history(&app).await;
+    let resp = app.get(&format!("/collections/{}/export?visits=true", col_id), Some(&cookie)).await;
+    let bundle = body_string(resp).await;
+
+    let (importer_id, importer_invite) = app.create_user("Importer").await;
+    let importer_cookie = app.login(&importer_invite).await;
+    let body = format!(
+        "name=Seeded&bundle={}",
+        url::form_urlencoded::byte_serialize(bundle.as_bytes()).collect::<String>()
+    );
+    let resp = app.post_form("/collections/import", &body, Some(&importer_cookie)).await;
+
+    let (new_id, owner_id, default_duration, default_tags): (String, String, Option<i64>, String) =
+        sqlx::query_as("SELECT id, owner_id, default_duration, default_tags FROM collections WHERE name = 'Seeded'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    common::assert_redirect(&resp, &format!("/collections/{}", new_id));
+    assert_eq!(owner_id, importer_id);
+    assert_eq!(default_duration, Some(2));
+    assert_eq!(default_tags, "team");
+
+    // Entries start unread, with their own schedule and the collection's tags
+    let (entry_id, user_id, duration, dismissed_at): (String, String, i64, Option<String>) =
+        sqlx::query_as("SELECT id, user_id, duration, dismissed_at FROM entries WHERE collection_id = ?")
+            .bind(&new_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(user_id, importer_id);
+    assert_eq!(duration, 3);
+    assert_eq!(dismissed_at, None);
+    let (visits,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM visits WHERE entry_id = ?")
+        .bind(&entry_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(visits, 0);
+
+    let tags: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT t.name, t.default_interval FROM tags t JOIN entry_tags et ON et.tag_id = t.id WHERE et.entry_id = ? AND t.collection_id = ? ORDER BY t.name",
+    )
+    .bind(&entry_id)
+    .bind(&new_id)
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    assert_eq!(
+        tags,
+        vec![
+            ("essays".to_string(), None),
+            ("rust".to_string(), Some("months".to_string())),
+            ("team".to_string(), None),
+        ]
+    );
+
+    // The original collection is untouched
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entries WHERE collection_id = ?")
+        .bind(&col_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn import_rejects_invalid_bundles() {
+    let app = TestApp::new().await;
+    let (_, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app.post_form("/collections/import", "name=&bundle=not+json", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("valid collection export"));
+
+    // A personal export isn't a collection bundle
+    let resp = app.get("/export", Some(&cookie)).await;
+    let export = body_string(resp).await;
+    let body = format!("name=&bundle={}", url::form_urlencoded::byte_serialize(export.as_bytes()).collect::<String>());
+    let resp = app.post_form("/collections/import", &body, Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM collections")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn cli_imports_collection_bundle() {
+    let app = TestApp::new().await;
+    let (_, col_id, cookie) = collection_with_history(&app).await;
+    let resp = app.get(&format!("/collections/{}/export", col_id), Some(&cookie)).await;
+    let bundle = body_string(resp).await;
+    let (importer_id, _) = app.create_user("Importer").await;
+
+    let path = std::env::temp_dir().join(format!("interne-collection-{}.json", uuid::Uuid::new_v4()));
+    std::fs::write(&path, bundle).unwrap();
+    interne::cli::import_collection(&app.db, path.to_str().unwrap(), &importer_id).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let (name,): (String,) = sqlx::query_as("SELECT name FROM collections WHERE owner_id = ?")
+        .bind(&importer_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(name, "Team Reading");
+}
@@ -0,0 +1,225 @@
+use sqlx::sqlite::SqlitePoolOptions;
+
+/// Run the migration files up to (but not including) `stop_at` as plain SQL
+async fn migrate_before(pool: &sqlx::SqlitePool, stop_at: &str) {
+    let migrations = [
+        ("001", include_str!("../migrations/001_initial.sql")),
+        ("002", include_str!("../migrations/002_timestamps.sql")),
+        ("003", include_str!("../migrations/003_visit_notes.sql")),
+        ("004", include_str!("../migrations/004_entry_revisions.sql")),
+        ("005", include_str!("../migrations/005_user_settings.sql")),
+        ("006", include_str!("../migrations/006_entry_priority.sql")),
+        ("007", include_str!("../migrations/007_tag_namespaces.sql")),
+        ("008", include_str!("../migrations/008_tag_schedules.sql")),
+        ("009", include_str!("../migrations/009_collection_roles.sql")),
+        ("010", include_str!("../migrations/010_collection_invites.sql")),
+        ("011", include_str!("../migrations/011_owner_removal.sql")),
+        ("012", include_str!("../migrations/012_collection_defaults.sql")),
+        ("013", include_str!("../migrations/013_collection_events.sql")),
+        ("014", include_str!("../migrations/014_collection_share_links.sql")),
+        ("015", include_str!("../migrations/015_entry_comments.sql")),
+        ("016", include_str!("../migrations/016_nested_collections.sql")),
+        ("017", include_str!("../migrations/017_tag_schedule_checks.sql")),
+        ("018", include_str!("../migrations/018_ownership_handover.sql")),
+    ];
+    for (version, sql) in migrations {
+        if version == stop_at {
+            break;
+        }
+        sqlx::raw_sql(sql).execute(pool).await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn tag_namespaces_split_global_tags() {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    migrate_before(&pool, "007").await;
+
+    sqlx::raw_sql(
+        r#"
+        INSERT INTO users (id, name, invite_code) VALUES ('u1', 'One', 'i1'), ('u2', 'Two', 'i2');
+        INSERT INTO collections (id, owner_id, name, invite_code) VALUES ('c1', 'u1', 'Team', 'ci1');
+        INSERT INTO entries (id, user_id, collection_id, url, title, duration, interval) VALUES
+            ('e1', 'u1', NULL, 'https://a.com', 'A', 1, 'days'),
+            ('e2', 'u2', NULL, 'https://b.com', 'B', 1, 'days'),
+            ('e3', 'u1', 'c1', 'https://c.com', 'C', 1, 'days'),
+            ('e4', 'u1', NULL, 'https://d.com', 'D', 1, 'days');
+        INSERT INTO tags (id, name) VALUES ('t-rust', 'rust'), ('t-web', 'web'), ('t-stale', 'stale');
+        INSERT INTO entry_tags (entry_id, tag_id) VALUES
+            ('e1', 't-rust'), ('e2', 't-rust'), ('e3', 't-rust'), ('e4', 't-rust'), ('e1', 't-web');
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::raw_sql(include_str!("../migrations/007_tag_namespaces.sql"))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let tags: Vec<(String, Option<String>, Option<String>)> =
+        sqlx::query_as("SELECT name, user_id, collection_id FROM tags ORDER BY name, user_id, collection_id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        tags,
+        vec![
+            ("rust".to_string(), None, Some("c1".to_string())),
+            ("rust".to_string(), Some("u1".to_string()), None),
+            ("rust".to_string(), Some("u2".to_string()), None),
+            ("web".to_string(), Some("u1".to_string()), None),
+        ]
+    );
+
+    // Entries of the same owner share one tag row; every link survives
+    let links: Vec<(String, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT et.entry_id, t.user_id, t.collection_id
+        FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+        WHERE t.name = 'rust'
+        ORDER BY et.entry_id
+        "#,
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        links,
+        vec![
+            ("e1".to_string(), Some("u1".to_string()), None),
+            ("e2".to_string(), Some("u2".to_string()), None),
+            ("e3".to_string(), None, Some("c1".to_string())),
+            ("e4".to_string(), Some("u1".to_string()), None),
+        ]
+    );
+    let (rows,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_tags")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(rows, 5);
+}
+
+#[tokio::test]
+async fn tag_schedule_checks_keep_tags_and_links() {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    migrate_before(&pool, "017").await;
+
+    sqlx::raw_sql(
+        r#"
+        INSERT INTO users (id, name, invite_code) VALUES ('u1', 'One', 'i1');
+        INSERT INTO entries (id, user_id, url, title, duration, interval) VALUES
+            ('e1', 'u1', 'https://a.com', 'A', 1, 'days');
+        INSERT INTO tags (id, name, user_id, default_duration, default_interval) VALUES
+            ('t-news', 'news', 'u1', 1, 'hours'),
+            ('t-half', 'half', 'u1', 2, NULL),
+            ('t-bad', 'bad', 'u1', 3, 'fortnights');
+        INSERT INTO entry_tags (entry_id, tag_id) VALUES ('e1', 't-news'), ('e1', 't-half');
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::raw_sql(include_str!("../migrations/017_tag_schedule_checks.sql"))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let tags: Vec<(String, Option<i64>, Option<String>)> =
+        sqlx::query_as("SELECT id, default_duration, default_interval FROM tags ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        tags,
+        vec![
+            ("t-bad".to_string(), None, None),
+            ("t-half".to_string(), None, None),
+            ("t-news".to_string(), Some(1), Some("hours".to_string())),
+        ]
+    );
+    let (links,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_tags")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(links, 2);
+
+    let half_set = sqlx::query("UPDATE tags SET default_interval = NULL WHERE id = 't-news'")
+        .execute(&pool)
+        .await;
+    assert!(half_set.is_err());
+    let unknown = sqlx::query("UPDATE tags SET default_interval = 'fortnights' WHERE id = 't-news'")
+        .execute(&pool)
+        .await;
+    assert!(unknown.is_err());
+}
+
+#[tokio::test]
+async fn deleting_an_owner_keeps_tags_on_links_made_private() {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    migrate_before(&pool, "019").await;
+
+    sqlx::raw_sql(
+        r#"
+        INSERT INTO users (id, name, invite_code) VALUES ('u1', 'Owner', 'i1'), ('u2', 'Author', 'i2');
+        INSERT INTO collections (id, owner_id, name, invite_code) VALUES ('c1', 'u1', 'Team', 'ci1');
+        INSERT INTO entries (id, user_id, collection_id, url, title, duration, interval) VALUES
+            ('e1', 'u2', 'c1', 'https://a.com', 'A', 1, 'days');
+        INSERT INTO tags (id, name, user_id, collection_id, default_duration, default_interval) VALUES
+            ('t-rust', 'rust', NULL, 'c1', NULL, NULL),
+            ('t-news', 'news', NULL, 'c1', 1, 'hours'),
+            ('t-own-rust', 'rust', 'u2', NULL, NULL, NULL);
+        INSERT INTO entry_tags (entry_id, tag_id) VALUES ('e1', 't-rust'), ('e1', 't-news');
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query("DELETE FROM users WHERE id = 'u1'").execute(&pool).await.unwrap();
+
+    let (collection_id,): (Option<String>,) = sqlx::query_as("SELECT collection_id FROM entries WHERE id = 'e1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(collection_id, None);
+
+    // The author's existing tag is reused, and new ones keep their schedule
+    let tags: Vec<(String, Option<String>, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT t.name, CASE WHEN t.id = 't-own-rust' THEN t.id END, t.default_interval
+        FROM entry_tags et JOIN tags t ON t.id = et.tag_id
+        WHERE et.entry_id = 'e1' AND t.user_id = 'u2'
+        ORDER BY t.name
+        "#,
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        tags,
+        vec![
+            ("news".to_string(), None, Some("hours".to_string())),
+            ("rust".to_string(), Some("t-own-rust".to_string()), None),
+        ]
+    );
+    let (links,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM entry_tags WHERE entry_id = 'e1'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(links, 2);
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{assert_redirect, body_string, TestApp};
+
+#[tokio::test]
+async fn settings_page_requires_auth() {
+    let app = TestApp::new().await;
+    let resp = app.get("/settings", None).await;
+    assert_redirect(&resp, "/login");
+}
+
+#[tokio::test]
+async fn settings_page_shows_bookmarklet() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app.get("/settings", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("id=\"bookmarklet\""));
+    assert!(html.contains("/entries/quick-add"));
+}
+
+#[tokio::test]
+async fn saved_defaults_prefill_new_entry_form() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app
+        .post_form(
+            "/settings",
+            "default_duration=3&default_interval=weeks&default_collection_id=&default_tags=Reading%2C+later",
+            Some(&cookie),
+        )
+        .await;
+    assert_redirect(&resp, "/settings?saved=1");
+
+    let resp = app.get("/entries/new", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("value=\"3\""));
+    assert!(html.contains("value=\"weeks\" selected"));
+    assert!(html.contains("value=\"reading, later\""));
+}
+
+#[tokio::test]
+async fn settings_reject_zero_duration() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let resp = app
+        .post_form(
+            "/settings",
+            "default_duration=0&default_interval=days&default_tags=",
+            Some(&cookie),
+        )
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Duration must be at least 1"));
+
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM user_settings WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 0);
+}
+
+#[tokio::test]
+async fn quick_add_uses_saved_defaults() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    app.post_form(
+        "/settings",
+        "default_duration=2&default_interval=months&default_tags=inbox",
+        Some(&cookie),
+    )
+    .await;
+
+    let resp = app
+        .post_form("/entries/quick-add", "url=example.com&tags=later", Some(&cookie))
+        .await;
+    assert_redirect(&resp, "/");
+
+    let (duration, interval): (i64, String) =
+        sqlx::query_as("SELECT duration, interval FROM entries WHERE user_id = ?")
+            .bind(&user_id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(duration, 2);
+    assert_eq!(interval, "months");
+
+    let tags: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT t.name FROM tags t
+        JOIN entry_tags et ON et.tag_id = t.id
+        JOIN entries e ON e.id = et.entry_id
+        WHERE e.user_id = ?
+        ORDER BY t.name
+        "#,
+    )
+    .bind(&user_id)
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    let tags: Vec<String> = tags.into_iter().map(|(t,)| t).collect();
+    assert_eq!(tags, vec!["inbox", "later"]);
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{assert_hx_redirect, assert_redirect, body_string, TestApp};
+
+async fn share_token(app: &TestApp, collection_id: &str) -> Option<String> {
+    let (token,): (Option<String>,) = sqlx::query_as("SELECT share_token FROM collections WHERE id = ?")
+        .bind(collection_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    token
+}
+
+#[tokio::test]
+async fn shared_page_shows_entries_without_login() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let col_id = app.create_collection(&owner_id, "Reading List").await;
+
+    let body = format!(
+        "url=example.com&title=Good+Read&description=Worth+**it**&duration=1&interval=days&tags=essays&collection_id={}",
+        col_id
+    );
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    app.post_form("/entries", "url=example.org&title=Private+Note&duration=1&interval=days&tags=&collection_id=", Some(&cookie))
+        .await;
+
+    assert!(share_token(&app, &col_id).await.is_none());
+    let resp = app.post_form(&format!("/collections/{}/share", col_id), "", Some(&cookie)).await;
+    assert_redirect(&resp, &format!("/collections/{}", col_id));
+    let token = share_token(&app, &col_id).await.unwrap();
+    assert_eq!(token.len(), 32);
+
+    let resp = app.get(&format!("/shared/{}", token), None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Reading List"));
+    assert!(html.contains("Good Read"));
+    assert!(html.contains("<strong>it</strong>"));
+    assert!(html.contains("essays"));
+    assert!(!html.contains("Private Note"));
+    // Read-only: no visit tracking or edit links
+    assert!(!html.contains("/visit"));
+    assert!(!html.contains("/edit"));
+}
+
+#[tokio::test]
+async fn shared_feed_is_atom() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let col_id = app.create_collection(&owner_id, "Reading List").await;
+
+    let body = format!(
+        "url=example.com&title=Tom+%26+Jerry&description=A+*classic*&duration=1&interval=days&tags=cartoons&collection_id={}",
+        col_id
+    );
+    app.post_form("/entries", &body, Some(&cookie)).await;
+    app.post_form(&format!("/collections/{}/share", col_id), "", Some(&cookie)).await;
+    let token = share_token(&app, &col_id).await.unwrap();
+
+    let resp = app.get(&format!("/shared/{}/feed.atom", token), None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/atom+xml; charset=utf-8");
+    let xml = body_string(resp).await;
+    assert!(xml.contains(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#));
+    assert!(xml.contains("<title>Tom &#38; Jerry</title>"));
+    assert!(xml.contains(r#"<category term="cartoons"/>"#));
+    assert!(xml.contains("&#60;em&#62;classic&#60;/em&#62;"));
+}
+
+#[tokio::test]
+async fn revoked_and_replaced_links_stop_working() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let cookie = app.login(&owner_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+
+    app.post_form(&format!("/collections/{}/share", col_id), "", Some(&cookie)).await;
+    let first = share_token(&app, &col_id).await.unwrap();
+    app.post_form(&format!("/collections/{}/share", col_id), "", Some(&cookie)).await;
+    let second = share_token(&app, &col_id).await.unwrap();
+    assert_ne!(first, second);
+    assert_eq!(app.get(&format!("/shared/{}", first), None).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(app.get(&format!("/shared/{}", second), None).await.status(), StatusCode::OK);
+
+    let resp = app.delete(&format!("/collections/{}/share", col_id), Some(&cookie)).await;
+    assert_hx_redirect(&resp, &format!("/collections/{}", col_id));
+    assert!(share_token(&app, &col_id).await.is_none());
+    assert_eq!(app.get(&format!("/shared/{}", second), None).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(app.get(&format!("/shared/{}/feed.atom", second), None).await.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn only_admins_share_collections() {
+    let app = TestApp::new().await;
+    let (owner_id, _) = app.create_user("Owner").await;
+    let (editor_id, editor_invite) = app.create_user("Editor").await;
+    let cookie = app.login(&editor_invite).await;
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &editor_id).await;
+
+    app.post_form(&format!("/collections/{}/share", col_id), "", Some(&cookie)).await;
+    assert!(share_token(&app, &col_id).await.is_none());
+}
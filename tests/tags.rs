@@ -1,7 +1,7 @@
 mod common;
 
 use axum::http::StatusCode;
-use common::{body_string, TestApp};
+use common::{assert_redirect, body_string, TestApp};
 
 #[tokio::test]
 async fn tags_page_requires_auth() {
@@ -80,6 +80,22 @@ async fn tag_detail_shows_entries_for_tag() {
     assert!(html.contains("rust (1)"));
 }
 
+#[tokio::test]
+async fn tags_named_like_tag_screens_have_pages() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=https%3A%2F%2Fexample.com&title=Charts&description=&duration=3&interval=days&tags=graph%2C+manage%2Fold&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    for path in ["/tags/graph", "/tags/manage", "/tags/manage/old"] {
+        let resp = app.get(path, Some(&cookie)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(body_string(resp).await.contains("Charts"), "{}", path);
+    }
+}
+
 #[tokio::test]
 async fn tag_detail_shows_empty_for_nonexistent_tag() {
     let app = TestApp::new().await;
@@ -117,3 +133,684 @@ async fn tag_cloud_has_inline_styles() {
     assert!(html.contains("font-size:"));
     assert!(html.contains("hsl("));
 }
+
+#[tokio::test]
+async fn same_tag_name_is_separate_per_user() {
+    let app = TestApp::new().await;
+    let (user1_id, invite1) = app.create_user("User 1").await;
+    let (user2_id, invite2) = app.create_user("User 2").await;
+    let cookie1 = app.login(&invite1).await;
+    let cookie2 = app.login(&invite2).await;
+
+    let body = "url=example.com%2F1&title=First+Rust&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie1)).await;
+    let body = "url=example.com%2F2&title=Second+Rust&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie2)).await;
+
+    let owners: Vec<(Option<String>,)> = sqlx::query_as("SELECT user_id FROM tags WHERE name = 'rust' ORDER BY user_id")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    let mut expected = vec![Some(user1_id), Some(user2_id)];
+    expected.sort();
+    assert_eq!(owners.into_iter().map(|(o,)| o).collect::<Vec<_>>(), expected);
+
+    // Each user only resolves their own "rust"
+    let html = body_string(app.get("/tags/rust", Some(&cookie1)).await).await;
+    assert!(html.contains("First Rust"));
+    assert!(!html.contains("Second Rust"));
+}
+
+#[tokio::test]
+async fn collection_tags_are_shared_with_members() {
+    let app = TestApp::new().await;
+    let (owner_id, owner_invite) = app.create_user("Owner").await;
+    let (member_id, member_invite) = app.create_user("Member").await;
+    let (_outsider_id, outsider_invite) = app.create_user("Outsider").await;
+    let owner_cookie = app.login(&owner_invite).await;
+    let member_cookie = app.login(&member_invite).await;
+    let outsider_cookie = app.login(&outsider_invite).await;
+
+    let col_id = app.create_collection(&owner_id, "Team").await;
+    app.add_member(&col_id, &member_id).await;
+
+    let body = format!("url=example.com&title=Shared+Entry&duration=3&interval=days&tags=teamwork&collection_id={col_id}");
+    app.post_form("/entries", &body, Some(&owner_cookie)).await;
+
+    let (user_id, collection_id): (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT user_id, collection_id FROM tags WHERE name = 'teamwork'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(user_id, None);
+    assert_eq!(collection_id.as_deref(), Some(col_id.as_str()));
+
+    for cookie in [&owner_cookie, &member_cookie] {
+        let html = body_string(app.get("/tags", Some(cookie)).await).await;
+        assert!(html.contains("teamwork"));
+        let html = body_string(app.get("/tags/teamwork", Some(cookie)).await).await;
+        assert!(html.contains("Shared Entry"));
+    }
+
+    let html = body_string(app.get("/tags/teamwork", Some(&outsider_cookie)).await).await;
+    assert!(!html.contains("Shared Entry"));
+}
+
+#[tokio::test]
+async fn unused_tags_are_removed() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=old&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE user_id = ?")
+        .bind(&user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=new&collection_id=";
+    app.post_form(&format!("/entries/{entry_id}"), body, Some(&cookie)).await;
+
+    let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM tags")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(names, vec![("new".to_string(),)]);
+
+    app.delete(&format!("/entries/{entry_id}"), Some(&cookie)).await;
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tags")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 0);
+}
+
+async fn tag_id(app: &TestApp, user_id: &str, name: &str) -> String {
+    let (id,): (String,) = sqlx::query_as("SELECT id FROM tags WHERE user_id = ? AND name = ?")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    id
+}
+
+async fn entry_tag_names(app: &TestApp, title: &str) -> Vec<String> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT t.name FROM tags t
+        JOIN entry_tags et ON et.tag_id = t.id
+        JOIN entries e ON e.id = et.entry_id
+        WHERE e.title = ?
+        ORDER BY t.name
+        "#,
+    )
+    .bind(title)
+    .fetch_all(&app.db)
+    .await
+    .unwrap();
+    rows.into_iter().map(|(n,)| n).collect()
+}
+
+#[tokio::test]
+async fn manage_page_lists_tags() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let resp = app.get("/tag-manager", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Manage Tags"));
+    assert!(html.contains("rust"));
+    assert!(html.contains("1 link"));
+}
+
+#[tokio::test]
+async fn rename_tag_merges_into_existing_name() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com%2F1&title=Both&duration=3&interval=days&tags=rust%2C+rustlang&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=example.com%2F2&title=Only+Long&duration=3&interval=days&tags=rustlang&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let id = tag_id(&app, &user_id, "rustlang").await;
+    let resp = app
+        .post_form(&format!("/tag-manager/{id}/rename"), "name=Rust", Some(&cookie))
+        .await;
+    common::assert_redirect(&resp, "/tag-manager");
+
+    assert_eq!(entry_tag_names(&app, "Both").await, vec!["rust"]);
+    assert_eq!(entry_tag_names(&app, "Only Long").await, vec!["rust"]);
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tags")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 1);
+}
+
+#[tokio::test]
+async fn rename_tag_moves_its_children() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com%2F1&title=Rusty&duration=3&interval=days&tags=lang%2Frust%2C+language&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=example.com%2F2&title=Deep&duration=3&interval=days&tags=lang%2Frust%2Fasync%2C+languages%2Frust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=example.com%2F3&title=Parent&duration=3&interval=days&tags=lang&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let id = tag_id(&app, &user_id, "lang").await;
+    app.post_form(&format!("/tag-manager/{id}/rename"), "name=languages", Some(&cookie)).await;
+
+    assert_eq!(entry_tag_names(&app, "Parent").await, vec!["languages"]);
+    assert_eq!(entry_tag_names(&app, "Rusty").await, vec!["language", "languages/rust"]);
+    // An existing child with the new name is merged into
+    assert_eq!(entry_tag_names(&app, "Deep").await, vec!["languages/rust", "languages/rust/async"]);
+    assert_eq!(tag_names(&app).await, vec!["language", "languages", "languages/rust", "languages/rust/async"]);
+
+    // A tag can't be renamed into its own subtree
+    let id = tag_id(&app, &user_id, "languages").await;
+    let resp = app.post_form(&format!("/tag-manager/{id}/rename"), "name=languages/old", Some(&cookie)).await;
+    assert!(body_string(resp).await.contains("move under itself"));
+    assert_eq!(entry_tag_names(&app, "Parent").await, vec!["languages"]);
+}
+
+#[tokio::test]
+async fn rename_tag_rejects_blank_name() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let id = tag_id(&app, &user_id, "rust").await;
+    let resp = app
+        .post_form(&format!("/tag-manager/{id}/rename"), "name=+", Some(&cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("Enter a single tag name"));
+    assert_eq!(entry_tag_names(&app, "Entry").await, vec!["rust"]);
+}
+
+#[tokio::test]
+async fn merge_several_tags() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com%2F1&title=First&duration=3&interval=days&tags=js&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=example.com%2F2&title=Second&duration=3&interval=days&tags=javascript%2C+web&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let js = tag_id(&app, &user_id, "js").await;
+    let javascript = tag_id(&app, &user_id, "javascript").await;
+    let resp = app
+        .post_form(
+            "/tag-manager/merge",
+            &format!("tag_id={js}&tag_id={javascript}&name=JavaScript"),
+            Some(&cookie),
+        )
+        .await;
+    common::assert_redirect(&resp, "/tag-manager");
+
+    assert_eq!(entry_tag_names(&app, "First").await, vec!["javascript"]);
+    assert_eq!(entry_tag_names(&app, "Second").await, vec!["javascript", "web"]);
+}
+
+#[tokio::test]
+async fn delete_tag_removes_it_from_entries() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=rust%2C+web&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let id = tag_id(&app, &user_id, "web").await;
+    let resp = app.delete(&format!("/tag-manager/{id}"), Some(&cookie)).await;
+    common::assert_hx_redirect(&resp, "/tag-manager");
+
+    assert_eq!(entry_tag_names(&app, "Entry").await, vec!["rust"]);
+}
+
+#[tokio::test]
+async fn cannot_manage_other_users_tags() {
+    let app = TestApp::new().await;
+    let (user1_id, invite1) = app.create_user("User 1").await;
+    let (_user2_id, invite2) = app.create_user("User 2").await;
+    let cookie1 = app.login(&invite1).await;
+    let cookie2 = app.login(&invite2).await;
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=private&collection_id=";
+    app.post_form("/entries", body, Some(&cookie1)).await;
+    let id = tag_id(&app, &user1_id, "private").await;
+
+    app.post_form(&format!("/tag-manager/{id}/rename"), "name=hijacked", Some(&cookie2))
+        .await;
+    app.delete(&format!("/tag-manager/{id}"), Some(&cookie2)).await;
+
+    assert_eq!(entry_tag_names(&app, "Entry").await, vec!["private"]);
+    let html = body_string(app.get("/tag-manager", Some(&cookie2)).await).await;
+    assert!(!html.contains("private"));
+}
+
+#[tokio::test]
+async fn cleanup_removes_orphaned_tags() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    sqlx::query("INSERT INTO tags (id, name, user_id, created_at) VALUES (?, ?, ?, ?)")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind("orphan")
+        .bind(&user_id)
+        .bind(chrono::Utc::now().to_rfc3339())
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let html = body_string(app.get("/tag-manager", Some(&cookie)).await).await;
+    assert!(html.contains("orphan"));
+    assert!(html.contains("0 links"));
+
+    let resp = app.post_form("/tag-manager/cleanup", "", Some(&cookie)).await;
+    common::assert_redirect(&resp, "/tag-manager");
+
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM tags")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(count.0, 0);
+}
+
+async fn tag_names(app: &TestApp) -> Vec<String> {
+    let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM tags ORDER BY name")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    names.into_iter().map(|(name,)| name).collect()
+}
+
+#[tokio::test]
+async fn cleanup_only_touches_the_users_namespaces() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let (other_id, _) = app.create_user("Other User").await;
+    let cookie = app.login(&invite_code).await;
+    let col_id = app.create_collection(&other_id, "Theirs").await;
+
+    let now = chrono::Utc::now().to_rfc3339();
+    for (name, owner, collection) in [
+        ("mine", Some(&user_id), None),
+        ("theirs", Some(&other_id), None),
+        ("shared", None, Some(&col_id)),
+    ] {
+        sqlx::query("INSERT INTO tags (id, name, user_id, collection_id, created_at) VALUES (?, ?, ?, ?, ?)")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(name)
+            .bind(owner)
+            .bind(collection)
+            .bind(&now)
+            .execute(&app.db)
+            .await
+            .unwrap();
+    }
+    sqlx::query(
+        "INSERT INTO tags (id, name, user_id, created_at, default_duration, default_interval) VALUES (?, ?, ?, ?, 2, 'weeks')",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind("scheduled")
+    .bind(&user_id)
+    .bind(&now)
+    .execute(&app.db)
+    .await
+    .unwrap();
+
+    app.post_form("/tag-manager/cleanup", "", Some(&cookie)).await;
+    assert_eq!(tag_names(&app).await, vec!["scheduled", "shared", "theirs"]);
+
+    // Saving an entry only prunes the tags it dropped
+    let body = "url=example.com&title=Retagged&duration=3&interval=days&tags=old&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Retagged'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let body = "url=example.com&title=Retagged&duration=3&interval=days&tags=new&collection_id=";
+    app.post_form(&format!("/entries/{}", entry_id), body, Some(&cookie)).await;
+    assert_eq!(tag_names(&app).await, vec!["new", "scheduled", "shared", "theirs"]);
+}
+
+#[tokio::test]
+async fn parent_tag_includes_children() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com%2F1&title=Async+Article&duration=3&interval=days&tags=Rust+%2F+Async&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=example.com%2F2&title=Plain+Rust&duration=3&interval=days&tags=rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=example.com%2F3&title=Rustacean+Post&duration=3&interval=days&tags=rustacean&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let html = body_string(app.get("/tags/rust", Some(&cookie)).await).await;
+    assert!(html.contains("Async Article"));
+    assert!(html.contains("Plain Rust"));
+    assert!(!html.contains("Rustacean Post"));
+    assert!(html.contains("rust (2)"));
+    assert!(html.contains("href=\"/tags/rust/async\""));
+
+    let html = body_string(app.get("/tags/rust/async", Some(&cookie)).await).await;
+    assert!(html.contains("Async Article"));
+    assert!(!html.contains("Plain Rust"));
+    // Breadcrumb back to the parent
+    assert!(html.contains("<a href=\"/tags/rust\" class=\"tag-breadcrumb\">rust</a>"));
+    assert!(html.contains("async (1)"));
+}
+
+#[tokio::test]
+async fn tag_cloud_collapses_to_top_level() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Entry&duration=3&interval=days&tags=rust%2Fasync%2C+rust%2Fweb&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let html = body_string(app.get("/tags", Some(&cookie)).await).await;
+    assert!(html.contains(">rust/async</a>"));
+    assert!(html.contains(">rust/web</a>"));
+
+    let html = body_string(app.get("/tags?top=1", Some(&cookie)).await).await;
+    assert!(html.contains(">rust</a>"));
+    assert!(html.contains("title=\"1 link\""));
+    assert!(!html.contains(">rust/async</a>"));
+}
+
+async fn entry_schedule(app: &TestApp, title: &str) -> (i64, String) {
+    sqlx::query_as("SELECT duration, interval FROM entries WHERE title = ?")
+        .bind(title)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn tag_page_edits_default_schedule() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Daily&duration=1&interval=days&tags=news&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let id = tag_id(&app, &user_id, "news").await;
+
+    let html = body_string(app.get("/tags/news", Some(&cookie)).await).await;
+    assert!(html.contains("Default schedule"));
+    assert!(html.contains(&format!("/tag-manager/{}/schedule", id)));
+
+    let resp = app
+        .post_form(&format!("/tag-manager/{}/schedule", id), "default_duration=2&default_interval=weeks", Some(&cookie))
+        .await;
+    assert_redirect(&resp, "/tags/news");
+
+    let html = body_string(app.get("/tags/news", Some(&cookie)).await).await;
+    assert!(html.contains("value=\"2\""));
+    assert!(html.contains("value=\"weeks\" selected"));
+
+    let resp = app
+        .post_form(&format!("/tag-manager/{}/schedule", id), "default_duration=0&default_interval=weeks", Some(&cookie))
+        .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(body_string(resp).await.contains("Duration must be at least 1"));
+
+    // Blank clears it
+    app.post_form(&format!("/tag-manager/{}/schedule", id), "default_duration=&default_interval=days", Some(&cookie))
+        .await;
+    let schedule: (Option<i64>, Option<String>) =
+        sqlx::query_as("SELECT default_duration, default_interval FROM tags WHERE id = ?")
+            .bind(&id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(schedule, (None, None));
+}
+
+#[tokio::test]
+async fn new_entries_use_tag_schedule_unless_changed() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=First&duration=1&interval=days&tags=reference&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let id = tag_id(&app, &user_id, "reference").await;
+    app.post_form(&format!("/tag-manager/{}/schedule", id), "default_duration=1&default_interval=years", Some(&cookie))
+        .await;
+
+    // Left at the user's default schedule: the tag's schedule applies,
+    // including to child tags
+    let body = "url=example.com&title=Default&duration=1&interval=days&tags=reference%2Fdocs&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    assert_eq!(entry_schedule(&app, "Default").await, (1, "years".to_string()));
+
+    // A schedule chosen in the form wins, even when it's the user's default
+    let body = "url=example.com&title=Explicit&duration=3&interval=weeks&tags=reference&collection_id=&schedule_set=true";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    assert_eq!(entry_schedule(&app, "Explicit").await, (3, "weeks".to_string()));
+    let body = "url=example.com&title=Chosen&duration=1&interval=days&tags=reference&collection_id=&schedule_set=true";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    assert_eq!(entry_schedule(&app, "Chosen").await, (1, "days".to_string()));
+
+    let resp = app
+        .post_form("/entries/quick-add", "url=example.com&title=Quick&tags=reference", Some(&cookie))
+        .await;
+    assert_redirect(&resp, "/");
+    assert_eq!(entry_schedule(&app, "Quick").await, (1, "years".to_string()));
+}
+
+#[tokio::test]
+async fn scheduled_tags_survive_when_unused() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Daily&duration=1&interval=days&tags=news&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let id = tag_id(&app, &user_id, "news").await;
+    app.post_form(&format!("/tag-manager/{}/schedule", id), "default_duration=1&default_interval=hours", Some(&cookie))
+        .await;
+
+    let (entry_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Daily'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.delete(&format!("/entries/{}", entry_id), Some(&cookie)).await;
+
+    let body = "url=example.com&title=Later&duration=1&interval=days&tags=news&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    assert_eq!(entry_schedule(&app, "Later").await, (1, "hours".to_string()));
+}
+
+#[tokio::test]
+async fn import_uses_tag_schedule_for_entries_without_one() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=Daily&duration=1&interval=days&tags=news&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let id = tag_id(&app, &user_id, "news").await;
+    app.post_form(&format!("/tag-manager/{}/schedule", id), "default_duration=1&default_interval=hours", Some(&cookie))
+        .await;
+
+    let path = std::env::temp_dir().join(format!("interne-import-{}.json", uuid::Uuid::new_v4()));
+    let json = r#"[
+        {"id": "1", "url": "https://a.example.com", "title": "Unscheduled", "tags": ["News"]},
+        {"id": "2", "url": "https://b.example.com", "title": "Scheduled", "duration": 2, "interval": "weeks", "tags": ["news"]},
+        {"id": "3", "url": "https://c.example.com", "title": "Zero", "duration": 0, "interval": "weeks", "tags": ["news"]},
+        {"id": "4", "url": "https://d.example.com", "title": "Negative", "duration": -2, "interval": "weeks", "tags": ["other"]}
+    ]"#;
+    std::fs::write(&path, json).unwrap();
+    interne::cli::import_data(&app.db, path.to_str().unwrap(), &user_id).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(entry_schedule(&app, "Unscheduled").await, (1, "hours".to_string()));
+    assert_eq!(entry_schedule(&app, "Scheduled").await, (2, "weeks".to_string()));
+    // Durations under 1 fall back like missing ones: to the tag's schedule,
+    // or the user's default duration with the given interval
+    assert_eq!(entry_schedule(&app, "Zero").await, (1, "hours".to_string()));
+    assert_eq!(entry_schedule(&app, "Negative").await, (1, "weeks".to_string()));
+}
+
+#[tokio::test]
+async fn import_normalizes_hierarchical_tags() {
+    let app = TestApp::new().await;
+    let (user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=example.com&title=From+Form&duration=1&interval=days&tags=Rust+%2F+Async&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let path = std::env::temp_dir().join(format!("interne-import-{}.json", uuid::Uuid::new_v4()));
+    let json = r#"[{"id": "1", "url": "https://a.example.com", "title": "Imported", "tags": ["rust / async", "Rust//Async/", " web/ "]}]"#;
+    std::fs::write(&path, json).unwrap();
+    interne::cli::import_data(&app.db, path.to_str().unwrap(), &user_id).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(tag_names(&app).await, vec!["rust/async", "web"]);
+}
+
+#[tokio::test]
+async fn autocomplete_ranks_existing_tags_by_usage() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    for (title, tags) in [("One", "rust"), ("Two", "rust%2C+web"), ("Three", "rustacean")] {
+        let body = format!("url=example.com&title={}&duration=1&interval=days&tags={}&collection_id=", title, tags);
+        app.post_form("/entries", &body, Some(&cookie)).await;
+    }
+
+    let resp = app.get("/tags-api/autocomplete?tags=web%2C+rsu", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    let rust = html.find("data-tag=\"rust\"").expect("rust suggested");
+    let rustacean = html.find("data-tag=\"rustacean\"").expect("rustacean suggested");
+    assert!(rust < rustacean);
+    assert!(!html.contains("data-tag=\"web\""));
+
+    let html = body_string(app.get("/tags-api/autocomplete?tags=", Some(&cookie)).await).await;
+    assert!(!html.contains("tag-suggestion"));
+}
+
+#[tokio::test]
+async fn suggests_tags_from_same_domain() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    let body = "url=https%3A%2F%2Fwww.example.com%2Fa&title=A&duration=1&interval=days&tags=docs%2C+rust&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+    let body = "url=other.org&title=B&duration=1&interval=days&tags=news&collection_id=";
+    app.post_form("/entries", body, Some(&cookie)).await;
+
+    let html = body_string(app.get("/tags-api/suggest?url=example.com%2Fb&tags=rust", Some(&cookie)).await).await;
+    assert!(html.contains("Used on this site"));
+    assert!(html.contains("data-tag=\"docs\""));
+    assert!(!html.contains("data-tag=\"rust\""));
+    assert!(!html.contains("data-tag=\"news\""));
+}
+
+#[tokio::test]
+async fn suggestions_do_not_leak_other_users_tags() {
+    let app = TestApp::new().await;
+    let (_user1, invite1) = app.create_user("User One").await;
+    let (_user2, invite2) = app.create_user("User Two").await;
+    let cookie1 = app.login(&invite1).await;
+    let cookie2 = app.login(&invite2).await;
+
+    let body = "url=example.com&title=Secret&duration=1&interval=days&tags=secret&collection_id=";
+    app.post_form("/entries", body, Some(&cookie1)).await;
+
+    let html = body_string(app.get("/tags-api/autocomplete?tags=sec", Some(&cookie2)).await).await;
+    assert!(!html.contains("secret"));
+    let html = body_string(app.get("/tags-api/suggest?url=example.com", Some(&cookie2)).await).await;
+    assert!(!html.contains("secret"));
+}
+
+#[tokio::test]
+async fn tag_list_shows_availability_stats() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    for title in ["Seen", "Fresh"] {
+        let body = format!("url=example.com&title={}&duration=1&interval=weeks&tags=rust&collection_id=", title);
+        app.post_form("/entries", &body, Some(&cookie)).await;
+    }
+    let (seen_id,): (String,) = sqlx::query_as("SELECT id FROM entries WHERE title = 'Seen'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    app.post_form(&format!("/entries/{}/visit", seen_id), "", Some(&cookie)).await;
+
+    let html = body_string(app.get("/tags", Some(&cookie)).await).await;
+    assert!(html.contains("class=\"tag-stats\""));
+    assert!(html.contains("href=\"/tags/rust?filter=ready\">1</a>"));
+    assert!(html.contains("href=\"/tags/rust?filter=waiting\">1</a>"));
+    assert!(html.contains("href=\"/tags/rust?filter=unseen\">1</a>"));
+    assert!(html.contains("just now"));
+
+    let html = body_string(app.get("/tags/rust?filter=ready", Some(&cookie)).await).await;
+    assert!(html.contains("Fresh"));
+    assert!(!html.contains("Seen"));
+
+    let html = body_string(app.get("/tags/rust?filter=waiting", Some(&cookie)).await).await;
+    assert!(html.contains("Seen"));
+    assert!(!html.contains("Fresh"));
+
+    let html = body_string(app.get("/tags/rust", Some(&cookie)).await).await;
+    assert!(html.contains("Seen"));
+    assert!(html.contains("Fresh"));
+}
+
+#[tokio::test]
+async fn tag_page_shows_related_tags_and_graph() {
+    let app = TestApp::new().await;
+    let (_user_id, invite_code) = app.create_user("Test User").await;
+    let cookie = app.login(&invite_code).await;
+
+    for (title, tags) in [("One", "rust%2C+web"), ("Two", "rust%2C+web%2C+go"), ("Three", "go")] {
+        let body = format!("url=example.com&title={}&duration=1&interval=days&tags={}&collection_id=", title, tags);
+        app.post_form("/entries", &body, Some(&cookie)).await;
+    }
+
+    let html = body_string(app.get("/tags/web", Some(&cookie)).await).await;
+    assert!(html.contains("Often tagged together with"));
+    let rust = html.find("href=\"/tags/rust\">rust (2)</a>").expect("rust related");
+    let go = html.find("href=\"/tags/go\">go (1)</a>").expect("go related");
+    assert!(rust < go);
+
+    let resp = app.get("/tag-graph", Some(&cookie)).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let html = body_string(resp).await;
+    assert!(html.contains("<svg class=\"tag-graph\""));
+    assert_eq!(html.matches("class=\"tag-graph-node\"").count(), 3);
+    assert!(html.contains("href=\"/all?tags=%2Brust%2C%2Bweb\">2</a>"));
+}